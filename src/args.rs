@@ -1,8 +1,9 @@
 ///Command line Arguments
 use std::path::PathBuf;
+use std::str::FromStr;
 
 /// Y-Fast-Trie Test Implementation
-#[derive(StructOpt, Debug)]
+#[derive(StructOpt, Debug, Clone)]
 #[structopt(name = "YFT", about = "Test Implementation of Dan Willard's Y-Fast-Trie")]
 pub struct Args {
     /// Source, where values should come from.
@@ -12,12 +13,65 @@ pub struct Args {
     /// Minimal height of lowest lss level
     #[structopt(short = "a", long, default_value = "10")]
     pub min_start_level: usize,
-    /// Evaluate the predecessor search steps (works with h = 23, 29)
+    /// Evaluate the predecessor search steps (works with h = 1 without -u, 12, 21, 23 - 26, 28 - 41 and 109)
     #[structopt(short = "d", long)]
     pub search_stats: bool,
-    /// Run multiple times (up to 40), each time with half much elements than before
+    /// Run multiple times, each time with less elements than before (see --sizes, --size-factor and --random-subsample)
+    /// The query file is only loaded once and used for all runs.
     #[structopt(short, long)]
     pub element_length_test: bool,
+    /// Numbers of elements that should be used by the element length test (comma separated, at most the number of input elements)
+    /// If not set, sizes are reduced by --size-factor
+    #[structopt(long = "sizes", raw(use_delimiter = "true"))]
+    pub sizes: Vec<usize>,
+    /// Factor that the number of elements is multiplied with for the next run of the element length test (between 0 and 1)
+    #[structopt(long = "size-factor", default_value = "0.5")]
+    pub size_factor: f64,
+    /// Use random subsets in the element length test instead of evenly spaced elements
+    #[structopt(long = "random-subsample")]
+    pub random_subsample: bool,
+    /// Run once per leaf level (comma separated), each run uses the level like -f
+    /// Use with -m and -t to compare memory and time of the leaf levels (e.g. suffix widths of h = 16)
+    #[structopt(long = "leaf-levels", raw(use_delimiter = "true"))]
+    pub leaf_levels: Vec<usize>,
+    /// Seed for --random-subsample and --level-estimator sample
+    #[structopt(long = "seed", default_value = "0")]
+    pub seed: u64,
+    /// Build tries with all combinations of --autotune-implementations, --autotune-start-levels and --autotune-top-levels
+    /// on a subsample of the values, measure query time (requires -q) and memory and print the pareto optimal configurations
+    #[structopt(long)]
+    pub autotune: bool,
+    /// Implementations tested by --autotune (works with h = 21, 23 - 26, 28 and 31 - 41)
+    #[structopt(long = "autotune-implementations", default_value = "21,23,31,32", raw(use_delimiter = "true"))]
+    pub autotune_implementations: Vec<usize>,
+    /// Leaf levels tested by --autotune
+    #[structopt(long = "autotune-start-levels", default_value = "6,8,10,12", raw(use_delimiter = "true"))]
+    pub autotune_start_levels: Vec<usize>,
+    /// Top levels tested by --autotune
+    #[structopt(long = "autotune-top-levels", default_value = "24,26,28,30,32", raw(use_delimiter = "true"))]
+    pub autotune_top_levels: Vec<usize>,
+    /// Number of values, that the tries of --autotune are built with
+    #[structopt(long = "autotune-sample", default_value = "1048576")]
+    pub autotune_sample: usize,
+    /// Number of queries, that the tries of --autotune are measured with
+    /// without --level-training-file they are taken from the even positions of the query file, h = 41 is trained with the odd positions
+    #[structopt(long = "autotune-queries", default_value = "100000")]
+    pub autotune_queries: usize,
+    /// Maximal memory in bytes, that the trie may use for all values
+    /// leaf and top level are raised until the predicted size fits (works with h = 21, 23 - 26, 28 and 31 - 41)
+    /// --autotune only recommends configurations within the budget
+    #[structopt(long = "memory-budget")]
+    pub memory_budget: Option<usize>,
+    /// Method to count the nodes per level, that the leaf and top level are calculated with
+    /// scan = one pass over the input per tested level
+    /// histogram = one pass over the input for all levels (exact, default)
+    /// sample = estimate from --level-sample-size random neighbour pairs
+    /// works with h = 16, 21, 23 - 26, 28 and 31 - 41
+    #[structopt(long = "level-estimator", default_value = "histogram")]
+    pub level_estimator: LevelEstimator,
+    /// Number of neighbour pairs, that are drawn by --level-estimator sample
+    #[structopt(long = "level-sample-size", default_value = "65536")]
+    pub level_sample_size: usize,
     /// If set leaf level will not be calculated.
     #[structopt(short = "f", long)]
     pub fixed_leaf_level: Option<usize>,
@@ -41,7 +95,7 @@ pub struct Args {
     /// 13 = no xft, successor list, binary search
     /// 14 = no xft, binary search
     /// 15 = element array split into smaller arrays
-    /// 16 = element array split into smaller 16bit value arrays (add and delete possible)
+    /// 16 = element array split into smaller bit packed arrays with leaf level bits per value (add and delete possible)
     /// 17 = element array split into smaller 16bit value arrays (add and delete possible), search in leaf level fist
     /// 20 = Fx hash map, leaf groups, child pointer
     /// 21 = Fx hash map, leaf groups, no child pointer, binary search input level
@@ -54,11 +108,52 @@ pub struct Args {
     /// 28 = im-rc hash map, leaf groups, no child pointer, binary search input level
     /// 29 = FNV hash map with binary search that doesnt cut in the middle (use -l option)
     /// 30 = FNV hash map, dynamic leaf groups, no child pointer, binary search input level
+    /// 31 = hashbrown hash map, leaf groups, no child pointer, binary search input level
+    /// 32 = packed linear probing hash map, leaf groups, no child pointer, binary search input level
+    /// 33 = mphf with fingerprints, leaf groups, no child pointer, binary search input level
+    /// 34 = FNV hash map with bloom filter per level, leaf groups, no child pointer, binary search input level
+    /// 35 = FNV hash map, leaf groups in eytzinger order, no child pointer, binary search input level
+    /// 36 = FNV hash map, leaf groups in blocks of 8 keys with branch free search, no child pointer
+    /// 37 = FNV hash map, leaf groups in blocks of 16 keys with branch free search, no child pointer
+    /// 38 = FNV hash map, elias-fano coded element array with rank and select, no child pointer
+    /// 39 = FNV hash map, interpolation search in the element array, no child pointer
+    /// 40 = FNV hash map, linear model with maximal error per leaf group, no child pointer (model errors are printed with -m)
+    /// 41 = FNV hash map, level search tree trained with the exit levels of --level-training-queries queries, no child pointer
+    /// (compare 23 and 35 - 40 with 102 to see the effect of the leaf layout)
     /// 100 = Use binary search instead of Y-Fast-Trie
     /// 101 = Use btree instead of Y-Fast-Trie
     /// 102 = Use Mixed binary anf linear Search instead of Y-Fast-Trie
+    /// 103 = Use interpolation search instead of Y-Fast-Trie (40 bit with -u, else 64 bit)
+    /// 104 = Use interpolation-sequential search instead of Y-Fast-Trie (40 bit with -u, else 64 bit)
+    /// 105 = Use exponential search from the position of the last result instead of Y-Fast-Trie (40 bit with -u, else 64 bit)
+    /// 106 = Use branchless binary search instead of Y-Fast-Trie (40 bit with -u, else 64 bit)
+    /// 107 = Use van Emde Boas tree with hashed clusters instead of Y-Fast-Trie (40 bit with -u, else 64 bit, add and delete possible)
+    /// 108 = Use X-Fast-Trie with all levels instead of Y-Fast-Trie (add and delete possible, level sizes are printed with -m)
+    /// 109 = Use Z-Fast-Trie over the leaf level prefixes with fat binary search instead of Y-Fast-Trie (40 bit with -u, else 64 bit, compare -d with h = 1)
+    /// 110 = Use B+-tree with packed 40 bit keys in 256 byte nodes instead of Y-Fast-Trie (add and delete possible, compare with h = 16 and 17)
     #[structopt(short="h", long, default_value = "1")]
     pub implementation: usize,
+    /// Load factor of hash tables with fixed size in percent (works with h = 32)
+    #[structopt(long = "level-load-factor", default_value = "75")]
+    pub level_load_factor: usize,
+    /// Bits per key of the fingerprints, that are checked before keys are compared (works with h = 33)
+    #[structopt(long = "fingerprint-bits", default_value = "8")]
+    pub fingerprint_bits: usize,
+    /// Bits per key of the bloom filter, that is asked before each level is searched (works with h = 34)
+    #[structopt(long = "filter-bits", default_value = "10")]
+    pub filter_bits: usize,
+    /// Number of threads, that build the levels of the Y-Fast-Trie concurrently (0 = one per core)
+    /// works with h = 21, 23 - 26 and 31 - 41
+    #[structopt(long = "threads", default_value = "1")]
+    pub threads: usize,
+    /// File with the queries, whose exit levels train the level search of h = 41 (without it h = 41 uses the midpoint search)
+    /// must differ from the query file, so the measured queries are not trained
+    /// --autotune trains with the odd positions of the query file, if it is not given
+    #[structopt(long = "level-training-file", parse(from_os_str))]
+    pub level_training_file: Option<PathBuf>,
+    /// Number of queries (evenly spaced from the training file), whose exit levels train the level search of h = 41
+    #[structopt(long = "level-training-queries", default_value = "100000")]
+    pub level_training_queries: usize,
     //percentage of left left searched space, that should be used for next query
     ///can only be used with h = 29
     /// should not be higher than 50 (else may cause infinite loop)
@@ -90,6 +185,23 @@ pub struct Args {
     /// A file where randomly generated Values from this run should be saved to
     #[structopt(short, long, parse(from_os_str))]
     pub  store: Option<PathBuf>,
+    /// Format of the file written with -s
+    /// load = serialized usize values, merged with an existing file (use with Load)
+    /// u40 = u40 values without any separator (use with U40)
+    /// u40s = serialized u40 values (use with U40S)
+    /// u40t = u40 values with their number at start (use with U40T)
+    /// u64s = serialized u64 values (use with U64S)
+    /// u64t = u64 values with their number at start (use with U64T)
+    #[structopt(long = "store-format", default_value = "load")]
+    pub  store_format: StoreFormat,
+    /// Compression of the file written with -s (none, gzip or zstd)
+    /// loaded files are decompressed depending on their magic bytes, independent of the file name
+    #[structopt(long = "store-compression", default_value = "none")]
+    pub  store_compression: Compression,
+    /// Build the Y-Fast-Trie while the input file is read, without loading it completely first
+    /// works only with h = 16 and U40, U40T or U64T input and requires -f and -g
+    #[structopt(long)]
+    pub  stream: bool,
     /// Log time
     #[structopt(short, long)]
     pub  time: bool,
@@ -113,7 +225,7 @@ pub struct Args {
 }
 
 // arg subcommand for number generation
-#[derive(Debug)] //this should not be necessary
+#[derive(Debug, Clone)] //this should not be necessary
 #[derive(StructOpt)]
 pub enum ValueSrc {
     Normal {
@@ -142,6 +254,29 @@ pub enum ValueSrc {
         length: usize,
         n: f64,
     },
+    /// IPv4 addresses (32 bit) from randomly placed prefix blocks
+    /// blocks = number of prefix blocks, min_prefix & max_prefix = range of the block prefix lengths
+    Ipv4 {
+        length: usize,
+        blocks: usize,
+        min_prefix: usize,
+        max_prefix: usize,
+    },
+    /// Event timestamps in milliseconds (40 bit) with a diurnal rate variation
+    /// days = time span, peak_factor = rate at noon relative to rate at midnight
+    Timestamps {
+        length: usize,
+        days: usize,
+        peak_factor: f64,
+    },
+    /// Sparse object ids (40 bit) with shard ids in the highest bits
+    /// shards = number of shards, shard_bits = bits of the shard prefix, mean_gap = average gap between ids of one shard
+    SparseIds {
+        length: usize,
+        shards: usize,
+        shard_bits: usize,
+        mean_gap: usize,
+    },
     /// A file with ordered Numbers to create the Y-Fast-Trie
     Load {
         #[structopt(parse(from_os_str))]
@@ -173,4 +308,73 @@ pub enum ValueSrc {
         #[structopt(parse(from_os_str))]
         path: PathBuf,
     },
-}
\ No newline at end of file
+}
+
+/// file formats that can be written with the -s option
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StoreFormat {
+    Load,
+    U40,
+    U40S,
+    U40T,
+    U64S,
+    U64T,
+}
+
+impl FromStr for StoreFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "load" => Ok(StoreFormat::Load),
+            "u40" => Ok(StoreFormat::U40),
+            "u40s" => Ok(StoreFormat::U40S),
+            "u40t" => Ok(StoreFormat::U40T),
+            "u64s" => Ok(StoreFormat::U64S),
+            "u64t" => Ok(StoreFormat::U64T),
+            _ => Err(format!("Unknown store format {}, use load, u40, u40s, u40t, u64s or u64t", s)),
+        }
+    }
+}
+
+/// compression of written files, read files are decompressed depending on their magic bytes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Compression::None),
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            _ => Err(format!("Unknown compression {}, use none, gzip or zstd", s)),
+        }
+    }
+}
+
+/// method to count the nodes per trie level for the level selection
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LevelEstimator {
+    Scan,
+    Histogram,
+    Sample,
+}
+
+impl FromStr for LevelEstimator {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "scan" => Ok(LevelEstimator::Scan),
+            "histogram" => Ok(LevelEstimator::Histogram),
+            "sample" => Ok(LevelEstimator::Sample),
+            _ => Err(format!("Unknown level estimator {}, use scan, histogram or sample", s)),
+        }
+    }
+}
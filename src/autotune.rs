@@ -0,0 +1,180 @@
+/// this module searches good leaf levels, top levels and hash tables for an input
+/// every candidate trie is built on a subsample of the values and measured with a sample of the queries
+/// memory of the full input is predicted from the number of nodes per level of all values
+
+use std::time::Instant;
+use uint::u40;
+use args::Args;
+use log::{Log, MemProbe};
+use level_map::LevelMapConfig;
+use level_stats::LevelStats;
+use nmbrsrc;
+use yft40so_fx_hash_binsearch;
+use yft40so_fnv_binsearch;
+use yft40so_rust_hash_binsearch;
+use yft40so_boomphf_binsearch;
+use yft40so_boomphf_para_binsearch;
+use yft40so_im_binsearch;
+use yft40so_hash_brown_binsearch;
+use yft40so_packed_binsearch;
+use yft40so_mphf_binsearch;
+use yft40so_fnv_bloom_binsearch;
+use yft40so_fnv_eytzinger_binsearch;
+use yft40so_fnv_block8_binsearch;
+use yft40so_fnv_block16_binsearch;
+use yft40so_fnv_elias_fano_binsearch;
+use yft40so_fnv_interpolation_binsearch;
+use yft40so_fnv_linear_model_binsearch;
+use yft40so_fnv_trained_binsearch;
+
+const BIT_LENGTH: usize = 40;
+
+/// one tested configuration
+pub struct Candidate {
+    pub implementation: usize,
+    pub start_level: usize,
+    pub top_level: usize,
+    pub ns_per_query: f64,
+    // bytes of the trie built from the subsample
+    pub bytes: usize,
+    // predicted bytes of the trie built from the full input
+    pub estimated_bytes: usize,
+}
+
+impl Candidate {
+    /// true if other is at least as good in time and memory and better in one of them
+    fn dominated_by(&self, other: &Candidate) -> bool {
+        other.ns_per_query <= self.ns_per_query && other.estimated_bytes <= self.estimated_bytes
+            && (other.ns_per_query < self.ns_per_query || other.estimated_bytes < self.estimated_bytes)
+    }
+
+    fn to_result(&self, info: &str) -> String {
+        format!("info={}\timplementation={}\tstart_level={}\ttop_level={}\tns_per_query={}\tbytes={}\testimated_bytes={}",
+                info, self.implementation, self.start_level, self.top_level, self.ns_per_query, self.bytes, self.estimated_bytes)
+    }
+}
+
+///values must be sorted ascending!
+pub fn run(values: Vec<u40>, queries: Vec<u40>, args: &Args, log: &mut Log) -> Vec<Candidate> {
+    let sample = nmbrsrc::subsample(&values, args.autotune_sample, None);
+    let stats = LevelStats::new(&values, args);
+    log.log_time("autotune level stats calculated");
+    //queries keep the order of the query file
+    //h = 41 is not measured with its training queries, without training file it is trained with the other half of the queries
+    let (queries, training_queries) = match args.level_training_file {
+        Some(ref file) => {
+            assert!(args.queries.as_ref() != Some(file), "--level-training-file must differ from the query file (-q)");
+            (queries, nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect())
+        }
+        None => split_queries(&queries),
+    };
+    let queries = nmbrsrc::subsample(&queries, args.autotune_queries, None);
+    let training_queries = nmbrsrc::subsample(&training_queries, args.level_training_queries, None);
+    log.log_time(&format!("autotune sample created\telements={}\tqueries={}\ttraining_queries={}", sample.len(), queries.len(), training_queries.len()));
+
+    let mut candidates = Vec::new();
+    for implementation in &args.autotune_implementations {
+        for start_level in &args.autotune_start_levels {
+            for top_level in &args.autotune_top_levels {
+                if start_level >= top_level || *top_level > BIT_LENGTH {
+                    continue;
+                }
+                let mut candidate_args = args.clone();
+                candidate_args.implementation = *implementation;
+                candidate_args.fixed_leaf_level = Some(*start_level);
+                candidate_args.fixed_top_level = Some(*top_level);
+                //the budget is meant for the full input and applied to the measured candidates
+                candidate_args.memory_budget = None;
+                let (ns_per_query, bytes, estimated_bytes) = measure(&sample, &stats, &queries, &training_queries, &candidate_args, log);
+                let candidate = Candidate {
+                    implementation: *implementation,
+                    start_level: *start_level,
+                    top_level: *top_level,
+                    ns_per_query,
+                    bytes,
+                    estimated_bytes,
+                };
+                log.print_result(candidate.to_result("autotune candidate"));
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    let pareto = pareto_front(candidates);
+    for candidate in &pareto {
+        log.print_result(candidate.to_result("autotune pareto"));
+    }
+    match recommend(&pareto, args.memory_budget) {
+        Some(candidate) => log.print_result(candidate.to_result("autotune recommendation")),
+        None => log.print_result(format!("info=autotune recommendation\tmemory_budget={}\tnone=true", args.memory_budget.unwrap_or(0))),
+    }
+    pareto
+}
+
+/// splits the queries into two disjoint halves (even and odd positions)
+pub fn split_queries(queries: &Vec<u40>) -> (Vec<u40>, Vec<u40>) {
+    (queries.iter().step_by(2).cloned().collect(), queries.iter().skip(1).step_by(2).cloned().collect())
+}
+
+/// returns (nanoseconds per query, allocated bytes, predicted bytes for the values of stats)
+fn measure(sample: &Vec<u40>, stats: &LevelStats, queries: &Vec<u40>, training_queries: &Vec<u40>, args: &Args, log: &mut Log) -> (f64, usize, usize) {
+    let config = LevelMapConfig::from_args(args);
+    macro_rules! measureyft40 {
+        (  $yft:ty ) => {
+            measureyft40!($yft; |_yft: &mut $yft| {})
+        };
+        (  $yft:ty; $prepare:expr ) => {
+            {
+                let probe = MemProbe::new();
+                let mut yft = <$yft>::new(sample.clone(), args, log);
+                $prepare(&mut yft);
+                let bytes = probe.bytes();
+                let start = Instant::now();
+                //results are counted, so the queries can't be optimized away
+                let found = queries.iter().filter(|q| yft.predecessor(**q).is_some()).count();
+                let elapsed = start.elapsed();
+                debug_assert!(found <= queries.len());
+                let estimated_bytes = <$yft>::estimate_size(stats, args.fixed_leaf_level.unwrap(), args.fixed_top_level.unwrap(), &config);
+                (elapsed.as_nanos() as f64 / queries.len().max(1) as f64, bytes, estimated_bytes)
+            }
+        };
+    }
+
+    match args.implementation {
+        21 => measureyft40!(yft40so_fx_hash_binsearch::YFT),
+        23 => measureyft40!(yft40so_fnv_binsearch::YFT),
+        24 => measureyft40!(yft40so_rust_hash_binsearch::YFT),
+        25 => measureyft40!(yft40so_boomphf_binsearch::YFT),
+        26 => measureyft40!(yft40so_boomphf_para_binsearch::YFT),
+        28 => measureyft40!(yft40so_im_binsearch::YFT),
+        31 => measureyft40!(yft40so_hash_brown_binsearch::YFT),
+        32 => measureyft40!(yft40so_packed_binsearch::YFT),
+        33 => measureyft40!(yft40so_mphf_binsearch::YFT),
+        34 => measureyft40!(yft40so_fnv_bloom_binsearch::YFT),
+        35 => measureyft40!(yft40so_fnv_eytzinger_binsearch::YFT),
+        36 => measureyft40!(yft40so_fnv_block8_binsearch::YFT),
+        37 => measureyft40!(yft40so_fnv_block16_binsearch::YFT),
+        38 => measureyft40!(yft40so_fnv_elias_fano_binsearch::YFT),
+        39 => measureyft40!(yft40so_fnv_interpolation_binsearch::YFT),
+        40 => measureyft40!(yft40so_fnv_linear_model_binsearch::YFT),
+        41 => measureyft40!(yft40so_fnv_trained_binsearch::YFT; |yft: &mut yft40so_fnv_trained_binsearch::YFT| yft.train_level_search(training_queries)),
+        _ => panic!("autotune can not be used with -h {}, use 21, 23 - 26, 28 or 31 - 41", args.implementation),
+    }
+}
+
+/// candidates, that are not dominated by another one, sorted by query time
+pub fn pareto_front(candidates: Vec<Candidate>) -> Vec<Candidate> {
+    let dominated: Vec<bool> = candidates.iter().map(|c| candidates.iter().any(|other| c.dominated_by(other))).collect();
+    let mut pareto: Vec<Candidate> = candidates.into_iter().zip(dominated.into_iter()).filter(|(_, d)| !d).map(|(c, _)| c).collect();
+    pareto.sort_by(|a, b| a.ns_per_query.partial_cmp(&b.ns_per_query).unwrap());
+    pareto
+}
+
+/// fastest candidate within the budget
+/// without budget the candidate with the lowest product of time and memory
+pub fn recommend(pareto: &Vec<Candidate>, memory_budget: Option<usize>) -> Option<&Candidate> {
+    match memory_budget {
+        Some(budget) => pareto.iter().filter(|c| c.estimated_bytes <= budget).next(),
+        None => pareto.iter().min_by(|a, b| (a.ns_per_query * a.estimated_bytes as f64).partial_cmp(&(b.ns_per_query * b.estimated_bytes as f64)).unwrap()),
+    }
+}
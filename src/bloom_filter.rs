@@ -0,0 +1,148 @@
+/// this module contains a blocked bloom filter and a level map, that asks the filter before the hash table
+/// on sparse levels most lookups are misses, which can be answered by the filter with one cache miss
+
+use std::mem::size_of;
+use uint::u40;
+use level_map::{LevelMap, LevelMapConfig, LookupStats};
+
+pub type DataType = u40;
+
+/// filter size, if none is given
+pub const DEFAULT_BITS_PER_KEY: usize = 10;
+/// bits per block (one cache line)
+const BLOCK_BITS: usize = 512;
+const WORDS_PER_BLOCK: usize = BLOCK_BITS / 64;
+
+/// bloom filter, where all bits of a key are in the same cache line
+pub struct BlockedBloomFilter {
+    words: Vec<u64>,
+    blocks: usize,
+    // number of bits set per key
+    hashes: usize,
+}
+
+impl BlockedBloomFilter {
+    /// bits_per_key = size of the filter relative to the number of keys
+    pub fn new(keys: &Vec<DataType>, bits_per_key: usize) -> BlockedBloomFilter {
+        assert!(bits_per_key > 0);
+        let blocks = BlockedBloomFilter::blocks_for(keys.len(), bits_per_key);
+        //optimal number of hash functions is bits_per_key * ln 2
+        let hashes = ((bits_per_key as f64 * std::f64::consts::LN_2).round() as usize).max(1).min(16);
+        let mut filter = BlockedBloomFilter { words: vec![0; blocks * WORDS_PER_BLOCK], blocks, hashes };
+        for key in keys {
+            let (block, mut bit, step) = filter.positions(*key);
+            for _ in 0..filter.hashes {
+                filter.words[block * WORDS_PER_BLOCK + bit / 64] |= 1 << (bit % 64);
+                bit = (bit + step) % BLOCK_BITS;
+            }
+        }
+        filter
+    }
+
+    /// number of blocks of a filter with len keys
+    fn blocks_for(len: usize, bits_per_key: usize) -> usize {
+        let blocks = (len * bits_per_key + BLOCK_BITS - 1) / BLOCK_BITS;
+        if blocks == 0 { 1 } else { blocks }
+    }
+
+    /// heap size of a filter with len keys
+    pub fn estimate_heap_size(len: usize, bits_per_key: usize) -> usize {
+        BlockedBloomFilter::blocks_for(len, bits_per_key) * WORDS_PER_BLOCK * size_of::<u64>()
+    }
+
+    /// finalizer of murmur3, every bit of the hash depends on all bits of the key
+    /// (the low bits of a plain multiplicative hash depend only on the low bits of the key)
+    #[inline]
+    fn hash(key: DataType) -> u64 {
+        let mut hash = u64::from(key);
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xFF51_AFD7_ED55_8CCD);
+        hash ^= hash >> 33;
+        hash = hash.wrapping_mul(0xC4CE_B9FE_1A85_EC53);
+        hash ^ (hash >> 33)
+    }
+
+    /// block, first bit in block, distance between bits (double hashing)
+    #[inline]
+    fn positions(&self, key: DataType) -> (usize, usize, usize) {
+        let hash = BlockedBloomFilter::hash(key);
+        let block = (((hash >> 32) * self.blocks as u64) >> 32) as usize;
+        let bit = hash as usize % BLOCK_BITS;
+        //odd step, so all bits of a block can be reached
+        let step = ((hash >> 9) as usize % BLOCK_BITS) | 1;
+        (block, bit, step)
+    }
+
+    /// false if key is definitely not in the filter
+    #[inline]
+    pub fn may_contain(&self, key: DataType) -> bool {
+        let (block, mut bit, step) = self.positions(key);
+        let block = unsafe { self.words.get_unchecked(block * WORDS_PER_BLOCK..(block + 1) * WORDS_PER_BLOCK) };
+        for _ in 0..self.hashes {
+            if block[bit / 64] & (1 << (bit % 64)) == 0 {
+                return false;
+            }
+            bit = (bit + step) % BLOCK_BITS;
+        }
+        true
+    }
+
+    pub fn heap_size(&self) -> usize {
+        self.words.capacity() * size_of::<u64>()
+    }
+}
+
+/// level map, that asks a bloom filter before the hash table M
+pub struct FilteredLevelMap<M: LevelMap<DataType, DataType>> {
+    filter: BlockedBloomFilter,
+    map: M,
+}
+
+impl<M: LevelMap<DataType, DataType>> FilteredLevelMap<M> {
+    pub fn new(keys: Vec<DataType>, values: Vec<DataType>, bits_per_key: usize, config: &LevelMapConfig) -> FilteredLevelMap<M> {
+        let filter = BlockedBloomFilter::new(&keys, bits_per_key);
+        FilteredLevelMap { filter, map: M::from_sorted_pairs_with_config(keys, values, config) }
+    }
+}
+
+impl<M: LevelMap<DataType, DataType>> LevelMap<DataType, DataType> for FilteredLevelMap<M> {
+    fn from_sorted_pairs(keys: Vec<DataType>, values: Vec<DataType>) -> Self {
+        FilteredLevelMap::new(keys, values, DEFAULT_BITS_PER_KEY, &LevelMapConfig::default())
+    }
+    fn from_sorted_pairs_with_config(keys: Vec<DataType>, values: Vec<DataType>, config: &LevelMapConfig) -> Self {
+        FilteredLevelMap::new(keys, values, config.filter_bits, config)
+    }
+    #[inline]
+    fn get(&self, key: &DataType) -> Option<&DataType> {
+        if !self.filter.may_contain(*key) {
+            return None;
+        }
+        self.map.get(key)
+    }
+    /// counts the filter block as probe, lookups answered by the filter alone and lookups where the filter was wrong
+    #[inline]
+    fn get_with_stats(&self, key: &DataType, stats: &mut LookupStats) -> Option<&DataType> {
+        stats.probes += 1;
+        if !self.filter.may_contain(*key) {
+            stats.avoided_lookups += 1;
+            return None;
+        }
+        let result = self.map.get_with_stats(key, stats);
+        if result.is_none() {
+            stats.false_positives += 1;
+        }
+        result
+    }
+    fn len(&self) -> usize {
+        self.map.len()
+    }
+    fn heap_size(&self) -> usize {
+        self.filter.heap_size() + self.map.heap_size()
+    }
+    fn heap_size_is_estimated(&self) -> bool {
+        self.map.heap_size_is_estimated()
+    }
+    fn estimate_heap_size(len: usize, config: &LevelMapConfig) -> usize {
+        BlockedBloomFilter::estimate_heap_size(len, config.filter_bits) + M::estimate_heap_size(len, config)
+    }
+}
@@ -0,0 +1,605 @@
+/// this module contains a cache conscious B+-tree for 40 bit keys as dynamic baseline for the split tries
+/// keys are split into the highest 8 bits and the lowest 32 bits, stored in two arrays of the node (5 bytes per key)
+/// a node search counts the smaller high bytes with simd and then the smaller low words of the keys with the same high byte
+/// every node fills 256 bytes (4 cache lines), children are u32 indices into one vector per node type
+
+use std::mem::size_of;
+use uint::u40;
+use log::Log;
+use heap_size::HeapSize;
+use predecessor_set::PredecessorSet;
+use simd_search;
+
+pub type DataType = u40;
+
+const LEAF_CAPACITY: usize = 50;
+const INNER_CAPACITY: usize = 27;
+/// nodes with less keys are merged with or filled from a neighbour (except the root)
+const LEAF_MIN: usize = LEAF_CAPACITY / 2;
+const INNER_MIN: usize = INNER_CAPACITY / 2;
+
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+struct Leaf {
+    lo: [u32; LEAF_CAPACITY],
+    hi: [u8; LEAF_CAPACITY],
+    len: u8,
+}
+
+// keys of child i are >= key i - 1 and < key i
+#[derive(Clone, Copy)]
+#[repr(C, align(64))]
+struct Inner {
+    lo: [u32; INNER_CAPACITY],
+    children: [u32; INNER_CAPACITY + 1],
+    hi: [u8; INNER_CAPACITY],
+    len: u8,
+}
+
+impl HeapSize for Leaf {}
+
+impl HeapSize for Inner {}
+
+impl Leaf {
+    fn empty() -> Leaf {
+        Leaf { lo: [0; LEAF_CAPACITY], hi: [0; LEAF_CAPACITY], len: 0 }
+    }
+
+    #[inline]
+    fn key(&self, pos: usize) -> DataType {
+        get_key(&self.hi, &self.lo, pos)
+    }
+}
+
+impl Inner {
+    fn empty() -> Inner {
+        Inner { lo: [0; INNER_CAPACITY], children: [0; INNER_CAPACITY + 1], hi: [0; INNER_CAPACITY], len: 0 }
+    }
+
+    #[inline]
+    fn key(&self, pos: usize) -> DataType {
+        get_key(&self.hi, &self.lo, pos)
+    }
+
+    /// position of the child, that contains query
+    #[inline]
+    fn child_position(&self, query: DataType) -> usize {
+        upper_bound(&self.hi, &self.lo, self.len as usize, query)
+    }
+}
+
+/// result of an insert into a subtree
+enum Insert {
+    Exists,
+    Done,
+    // first key of the new right node, index of the new right node
+    Split(DataType, u32),
+}
+
+#[inline]
+fn split_key(key: DataType) -> (u8, u32) {
+    let key = u64::from(key);
+    ((key >> 32) as u8, key as u32)
+}
+
+#[inline]
+fn get_key(hi: &[u8], lo: &[u32], pos: usize) -> DataType {
+    DataType::from((hi[pos] as u64) << 32 | lo[pos] as u64)
+}
+
+/// first position of the len keys, that is not smaller than query
+#[inline]
+fn lower_bound(hi: &[u8], lo: &[u32], len: usize, query: DataType) -> usize {
+    let (query_hi, query_lo) = split_key(query);
+    let hi = &hi[..len];
+    let start = simd_search::lower_bound_u8(hi, query_hi);
+    //keys with the same high byte
+    let end = if query_hi == u8::max_value() { len } else { start + simd_search::lower_bound_u8(&hi[start..], query_hi + 1) };
+    start + simd_search::lower_bound_u32(&lo[start..end], query_lo)
+}
+
+/// first position of the len keys, that is greater than query
+#[inline]
+fn upper_bound(hi: &[u8], lo: &[u32], len: usize, query: DataType) -> usize {
+    let pos = lower_bound(hi, lo, len, query);
+    if pos < len && get_key(hi, lo, pos) == query { pos + 1 } else { pos }
+}
+
+/// shifts the values behind pos one to the right, len values are used
+#[inline]
+fn array_insert<T: Copy>(array: &mut [T], len: usize, pos: usize, value: T) {
+    array.copy_within(pos..len, pos + 1);
+    array[pos] = value;
+}
+
+#[inline]
+fn array_remove<T: Copy>(array: &mut [T], len: usize, pos: usize) {
+    array.copy_within(pos + 1..len, pos);
+}
+
+#[inline]
+fn insert_key(hi: &mut [u8], lo: &mut [u32], len: usize, pos: usize, key: DataType) {
+    let (key_hi, key_lo) = split_key(key);
+    array_insert(hi, len, pos, key_hi);
+    array_insert(lo, len, pos, key_lo);
+}
+
+#[inline]
+fn remove_key(hi: &mut [u8], lo: &mut [u32], len: usize, pos: usize) {
+    array_remove(hi, len, pos);
+    array_remove(lo, len, pos);
+}
+
+/// dynamic B+-tree with packed 40 bit keys
+pub struct BTree {
+    leaves: Vec<Leaf>,
+    inners: Vec<Inner>,
+    // indices of removed nodes, that are reused
+    free_leaves: Vec<u32>,
+    free_inners: Vec<u32>,
+    root: u32,
+    // number of inner levels, 0 = root is a leaf
+    height: usize,
+    len: usize,
+}
+
+impl BTree {
+    pub fn new() -> BTree {
+        BTree {
+            leaves: vec![Leaf::empty()],
+            inners: Vec::new(),
+            free_leaves: Vec::new(),
+            free_inners: Vec::new(),
+            root: 0,
+            height: 0,
+            len: 0,
+        }
+    }
+
+    ///elements must be sorted ascending! leaves and inner nodes are filled evenly and as full as possible
+    pub fn from_sorted(mut elements: Vec<DataType>, log: &mut Log) -> BTree {
+        elements.dedup();
+        let mut tree = BTree::new();
+        if elements.len() == 0 {
+            return tree;
+        }
+        tree.len = elements.len();
+        tree.leaves.clear();
+        // (smallest key, index) of the nodes of the current level
+        let mut level = Vec::new();
+        let mut start = 0;
+        for size in BTree::node_sizes(elements.len(), LEAF_CAPACITY) {
+            let mut leaf = Leaf::empty();
+            for (pos, key) in elements[start..start + size].iter().enumerate() {
+                let (key_hi, key_lo) = split_key(*key);
+                leaf.hi[pos] = key_hi;
+                leaf.lo[pos] = key_lo;
+            }
+            leaf.len = size as u8;
+            level.push((elements[start], tree.leaves.len() as u32));
+            tree.leaves.push(leaf);
+            start += size;
+        }
+        log.log_mem("leaves filled").log_time("leaves filled");
+
+        while level.len() > 1 {
+            let mut next_level = Vec::new();
+            let mut start = 0;
+            for size in BTree::node_sizes(level.len(), INNER_CAPACITY + 1) {
+                let mut inner = Inner::empty();
+                inner.children[0] = level[start].1;
+                for pos in 1..size {
+                    let (key_hi, key_lo) = split_key(level[start + pos].0);
+                    inner.hi[pos - 1] = key_hi;
+                    inner.lo[pos - 1] = key_lo;
+                    inner.children[pos] = level[start + pos].1;
+                }
+                inner.len = (size - 1) as u8;
+                next_level.push((level[start].0, tree.inners.len() as u32));
+                tree.inners.push(inner);
+                start += size;
+            }
+            level = next_level;
+            tree.height += 1;
+        }
+        tree.root = level[0].1;
+        log.log_mem("inner nodes filled").log_time("inner nodes filled");
+        tree
+    }
+
+    /// splits len entries into as few nodes as possible, their sizes differ by at most one
+    /// with more than one node, every node is at least half full
+    fn node_sizes(len: usize, capacity: usize) -> Vec<usize> {
+        let nodes = (len + capacity - 1) / capacity;
+        (0..nodes).map(|node| len / nodes + (node < len % nodes) as usize).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    fn alloc_leaf(&mut self, leaf: Leaf) -> u32 {
+        match self.free_leaves.pop() {
+            Some(index) => {
+                self.leaves[index as usize] = leaf;
+                index
+            }
+            None => {
+                self.leaves.push(leaf);
+                (self.leaves.len() - 1) as u32
+            }
+        }
+    }
+
+    fn alloc_inner(&mut self, inner: Inner) -> u32 {
+        match self.free_inners.pop() {
+            Some(index) => {
+                self.inners[index as usize] = inner;
+                index
+            }
+            None => {
+                self.inners.push(inner);
+                (self.inners.len() - 1) as u32
+            }
+        }
+    }
+
+    /// leaf, that contains query
+    #[inline]
+    fn find_leaf(&self, query: DataType) -> &Leaf {
+        let mut node = self.root;
+        for _ in 0..self.height {
+            let inner = &self.inners[node as usize];
+            node = inner.children[inner.child_position(query)];
+        }
+        &self.leaves[node as usize]
+    }
+
+    pub fn contains(&self, query: DataType) -> bool {
+        let leaf = self.find_leaf(query);
+        let pos = lower_bound(&leaf.hi, &leaf.lo, leaf.len as usize, query);
+        pos < leaf.len as usize && leaf.key(pos) == query
+    }
+
+    /// largest element smaller than query
+    pub fn predecessor(&self, query: DataType) -> Option<DataType> {
+        let mut node = self.root;
+        // nearest subtree left of the path and its height, its maximum is the predecessor, if the leaf has no smaller key
+        let mut left_subtree = None;
+        for height in (0..self.height).rev() {
+            let inner = &self.inners[node as usize];
+            let pos = inner.child_position(query);
+            if pos > 0 {
+                left_subtree = Some((inner.children[pos - 1], height));
+            }
+            node = inner.children[pos];
+        }
+        let leaf = &self.leaves[node as usize];
+        let pos = lower_bound(&leaf.hi, &leaf.lo, leaf.len as usize, query);
+        if pos > 0 {
+            return Some(leaf.key(pos - 1));
+        }
+        let (mut node, height) = left_subtree?;
+        for _ in 0..height {
+            let inner = &self.inners[node as usize];
+            node = inner.children[inner.len as usize];
+        }
+        let leaf = &self.leaves[node as usize];
+        Some(leaf.key(leaf.len as usize - 1))
+    }
+
+    /// smallest element greater than query
+    pub fn successor(&self, query: DataType) -> Option<DataType> {
+        let mut node = self.root;
+        // nearest subtree right of the path and its height, its minimum is the successor, if the leaf has no greater key
+        let mut right_subtree = None;
+        for height in (0..self.height).rev() {
+            let inner = &self.inners[node as usize];
+            let pos = inner.child_position(query);
+            if pos < inner.len as usize {
+                right_subtree = Some((inner.children[pos + 1], height));
+            }
+            node = inner.children[pos];
+        }
+        let leaf = &self.leaves[node as usize];
+        let pos = upper_bound(&leaf.hi, &leaf.lo, leaf.len as usize, query);
+        if pos < leaf.len as usize {
+            return Some(leaf.key(pos));
+        }
+        let (mut node, height) = right_subtree?;
+        for _ in 0..height {
+            node = self.inners[node as usize].children[0];
+        }
+        Some(self.leaves[node as usize].key(0))
+    }
+
+    pub fn minimum(&self) -> Option<DataType> {
+        if self.len == 0 {
+            return None;
+        }
+        let mut node = self.root;
+        for _ in 0..self.height {
+            node = self.inners[node as usize].children[0];
+        }
+        Some(self.leaves[node as usize].key(0))
+    }
+
+    pub fn maximum(&self) -> Option<DataType> {
+        if self.len == 0 {
+            return None;
+        }
+        let mut node = self.root;
+        for _ in 0..self.height {
+            let inner = &self.inners[node as usize];
+            node = inner.children[inner.len as usize];
+        }
+        let leaf = &self.leaves[node as usize];
+        Some(leaf.key(leaf.len as usize - 1))
+    }
+
+    pub fn insert(&mut self, key: DataType) {
+        let (root, height) = (self.root, self.height);
+        match self.insert_into(root, height, key) {
+            Insert::Exists => return,
+            Insert::Done => {}
+            Insert::Split(separator, right) => {
+                //new root with the old root and its new neighbour
+                let mut inner = Inner::empty();
+                let (key_hi, key_lo) = split_key(separator);
+                inner.hi[0] = key_hi;
+                inner.lo[0] = key_lo;
+                inner.children[0] = root;
+                inner.children[1] = right;
+                inner.len = 1;
+                self.root = self.alloc_inner(inner);
+                self.height += 1;
+            }
+        }
+        self.len += 1;
+    }
+
+    fn insert_into(&mut self, node: u32, height: usize, key: DataType) -> Insert {
+        if height == 0 {
+            let leaf = &mut self.leaves[node as usize];
+            let len = leaf.len as usize;
+            let pos = lower_bound(&leaf.hi, &leaf.lo, len, key);
+            if pos < len && leaf.key(pos) == key {
+                return Insert::Exists;
+            }
+            if len < LEAF_CAPACITY {
+                insert_key(&mut leaf.hi, &mut leaf.lo, len, pos, key);
+                leaf.len += 1;
+                return Insert::Done;
+            }
+            //the right half is moved to a new leaf
+            let half = LEAF_CAPACITY / 2;
+            let mut right = Leaf::empty();
+            right.hi[..len - half].copy_from_slice(&leaf.hi[half..len]);
+            right.lo[..len - half].copy_from_slice(&leaf.lo[half..len]);
+            right.len = (len - half) as u8;
+            leaf.len = half as u8;
+            if pos <= half {
+                insert_key(&mut leaf.hi, &mut leaf.lo, half, pos, key);
+                leaf.len += 1;
+            } else {
+                insert_key(&mut right.hi, &mut right.lo, len - half, pos - half, key);
+                right.len += 1;
+            }
+            let separator = right.key(0);
+            return Insert::Split(separator, self.alloc_leaf(right));
+        }
+
+        let pos = self.inners[node as usize].child_position(key);
+        let child = self.inners[node as usize].children[pos];
+        let (separator, new_child) = match self.insert_into(child, height - 1, key) {
+            Insert::Split(separator, new_child) => (separator, new_child),
+            result => return result,
+        };
+        let inner = &mut self.inners[node as usize];
+        let len = inner.len as usize;
+        if len < INNER_CAPACITY {
+            insert_key(&mut inner.hi, &mut inner.lo, len, pos, separator);
+            array_insert(&mut inner.children, len + 1, pos + 1, new_child);
+            inner.len += 1;
+            return Insert::Done;
+        }
+        //full node: insert into copies with one more key, the middle key moves up
+        let mut hi = [0u8; INNER_CAPACITY + 1];
+        let mut lo = [0u32; INNER_CAPACITY + 1];
+        let mut children = [0u32; INNER_CAPACITY + 2];
+        hi[..len].copy_from_slice(&inner.hi);
+        lo[..len].copy_from_slice(&inner.lo);
+        children[..len + 1].copy_from_slice(&inner.children);
+        insert_key(&mut hi, &mut lo, len, pos, separator);
+        array_insert(&mut children, len + 1, pos + 1, new_child);
+        let half = (len + 1) / 2;
+        let mut right = Inner::empty();
+        let right_len = len - half;
+        right.hi[..right_len].copy_from_slice(&hi[half + 1..]);
+        right.lo[..right_len].copy_from_slice(&lo[half + 1..]);
+        right.children[..right_len + 1].copy_from_slice(&children[half + 1..]);
+        right.len = right_len as u8;
+        inner.hi[..half].copy_from_slice(&hi[..half]);
+        inner.lo[..half].copy_from_slice(&lo[..half]);
+        inner.children[..half + 1].copy_from_slice(&children[..half + 1]);
+        inner.len = half as u8;
+        let separator = get_key(&hi, &lo, half);
+        Insert::Split(separator, self.alloc_inner(right))
+    }
+
+    pub fn delete(&mut self, key: DataType) {
+        let (root, height) = (self.root, self.height);
+        if !self.delete_from(root, height, key) {
+            return;
+        }
+        self.len -= 1;
+        if self.height > 0 && self.inners[root as usize].len == 0 {
+            //root has only one child left
+            self.root = self.inners[root as usize].children[0];
+            self.free_inners.push(root);
+            self.height -= 1;
+        }
+    }
+
+    /// returns false, if key is not in the subtree
+    fn delete_from(&mut self, node: u32, height: usize, key: DataType) -> bool {
+        if height == 0 {
+            let leaf = &mut self.leaves[node as usize];
+            let len = leaf.len as usize;
+            let pos = lower_bound(&leaf.hi, &leaf.lo, len, key);
+            if pos == len || leaf.key(pos) != key {
+                return false;
+            }
+            remove_key(&mut leaf.hi, &mut leaf.lo, len, pos);
+            leaf.len -= 1;
+            return true;
+        }
+
+        let pos = self.inners[node as usize].child_position(key);
+        let child = self.inners[node as usize].children[pos];
+        if !self.delete_from(child, height - 1, key) {
+            return false;
+        }
+        let underflow = if height == 1 {
+            (self.leaves[child as usize].len as usize) < LEAF_MIN
+        } else {
+            (self.inners[child as usize].len as usize) < INNER_MIN
+        };
+        if underflow {
+            //the left neighbour is preferred
+            let left_pos = if pos > 0 { pos - 1 } else { pos };
+            if height == 1 {
+                self.rebalance_leaves(node, left_pos);
+            } else {
+                self.rebalance_inners(node, left_pos);
+            }
+        }
+        true
+    }
+
+    /// removes key left_pos and the child behind it from parent
+    fn remove_from_parent(&mut self, parent: u32, left_pos: usize) {
+        let parent = &mut self.inners[parent as usize];
+        let len = parent.len as usize;
+        remove_key(&mut parent.hi, &mut parent.lo, len, left_pos);
+        array_remove(&mut parent.children, len + 1, left_pos + 1);
+        parent.len -= 1;
+    }
+
+    /// merges the leaves left_pos and left_pos + 1 of parent or moves one key to the smaller one
+    fn rebalance_leaves(&mut self, parent: u32, left_pos: usize) {
+        let left_index = self.inners[parent as usize].children[left_pos] as usize;
+        let right_index = self.inners[parent as usize].children[left_pos + 1];
+        let mut left = self.leaves[left_index];
+        let mut right = self.leaves[right_index as usize];
+        let (left_len, right_len) = (left.len as usize, right.len as usize);
+        if left_len + right_len <= LEAF_CAPACITY {
+            left.hi[left_len..left_len + right_len].copy_from_slice(&right.hi[..right_len]);
+            left.lo[left_len..left_len + right_len].copy_from_slice(&right.lo[..right_len]);
+            left.len += right.len;
+            self.leaves[left_index] = left;
+            self.free_leaves.push(right_index);
+            self.remove_from_parent(parent, left_pos);
+            return;
+        }
+        if left_len < right_len {
+            let moved = right.key(0);
+            insert_key(&mut left.hi, &mut left.lo, left_len, left_len, moved);
+            left.len += 1;
+            remove_key(&mut right.hi, &mut right.lo, right_len, 0);
+            right.len -= 1;
+        } else {
+            let moved = left.key(left_len - 1);
+            insert_key(&mut right.hi, &mut right.lo, right_len, 0, moved);
+            right.len += 1;
+            left.len -= 1;
+        }
+        let (key_hi, key_lo) = split_key(right.key(0));
+        self.leaves[left_index] = left;
+        self.leaves[right_index as usize] = right;
+        let parent = &mut self.inners[parent as usize];
+        parent.hi[left_pos] = key_hi;
+        parent.lo[left_pos] = key_lo;
+    }
+
+    /// merges the inner nodes left_pos and left_pos + 1 of parent or rotates one key through the parent
+    fn rebalance_inners(&mut self, parent: u32, left_pos: usize) {
+        let left_index = self.inners[parent as usize].children[left_pos] as usize;
+        let right_index = self.inners[parent as usize].children[left_pos + 1];
+        let separator = self.inners[parent as usize].key(left_pos);
+        let mut left = self.inners[left_index];
+        let mut right = self.inners[right_index as usize];
+        let (left_len, right_len) = (left.len as usize, right.len as usize);
+        if left_len + right_len + 1 <= INNER_CAPACITY {
+            insert_key(&mut left.hi, &mut left.lo, left_len, left_len, separator);
+            left.hi[left_len + 1..left_len + 1 + right_len].copy_from_slice(&right.hi[..right_len]);
+            left.lo[left_len + 1..left_len + 1 + right_len].copy_from_slice(&right.lo[..right_len]);
+            left.children[left_len + 1..left_len + 2 + right_len].copy_from_slice(&right.children[..right_len + 1]);
+            left.len += right.len + 1;
+            self.inners[left_index] = left;
+            self.free_inners.push(right_index);
+            self.remove_from_parent(parent, left_pos);
+            return;
+        }
+        let new_separator = if left_len < right_len {
+            insert_key(&mut left.hi, &mut left.lo, left_len, left_len, separator);
+            left.children[left_len + 1] = right.children[0];
+            left.len += 1;
+            let new_separator = right.key(0);
+            remove_key(&mut right.hi, &mut right.lo, right_len, 0);
+            array_remove(&mut right.children, right_len + 1, 0);
+            right.len -= 1;
+            new_separator
+        } else {
+            insert_key(&mut right.hi, &mut right.lo, right_len, 0, separator);
+            array_insert(&mut right.children, right_len + 1, 0, left.children[left_len]);
+            right.len += 1;
+            left.len -= 1;
+            left.key(left_len - 1)
+        };
+        self.inners[left_index] = left;
+        self.inners[right_index as usize] = right;
+        let (key_hi, key_lo) = split_key(new_separator);
+        let parent = &mut self.inners[parent as usize];
+        parent.hi[left_pos] = key_hi;
+        parent.lo[left_pos] = key_lo;
+    }
+
+    ///bytes on the heap
+    pub fn heap_size(&self) -> usize {
+        self.leaves.heap_size() + self.inners.heap_size() + (self.free_leaves.capacity() + self.free_inners.capacity()) * size_of::<u32>()
+    }
+
+    ///prints number of nodes and their fill level
+    pub fn print_stats(&self, log: &Log) {
+        let leaves = self.leaves.len() - self.free_leaves.len();
+        let inners = self.inners.len() - self.free_inners.len();
+        log.print_result(format!("height={}\tleaves={}\tinner_nodes={}\tleaf_fill={}\tnode_bytes={}",
+                                 self.height + 1, leaves, inners, self.len as f64 / (leaves * LEAF_CAPACITY) as f64, size_of::<Leaf>()));
+        log.print_result(format!("memory=total\tbytes={}", self.heap_size()));
+    }
+}
+
+impl PredecessorSet<DataType> for BTree {
+    fn predecessor(&self, number: DataType) -> Option<DataType> {
+        self.predecessor(number)
+    }
+    fn insert(&mut self, element: DataType) {
+        self.insert(element)
+    }
+    fn delete(&mut self, element: DataType) {
+        self.delete(element)
+    }
+    fn successor(&self, number: DataType) -> Option<DataType> {
+        self.successor(number)
+    }
+    fn minimum(&self) -> Option<DataType> {
+        self.minimum()
+    }
+    fn maximum(&self) -> Option<DataType> {
+        self.maximum()
+    }
+    fn contains(&self, number: DataType) -> bool {
+        self.contains(number)
+    }
+}
@@ -0,0 +1,204 @@
+/// this module contains an elias-fano coded element array for the static tries
+/// every element is split into low bits (stored packed) and high bits (stored unary in a bit vector)
+/// with n elements and a largest element u, an element needs 2 + log(u / n) bits instead of 40
+/// select (element at a position) and rank (number of smaller elements) use sampled positions of the ones and zeros
+
+use std::mem::size_of;
+use uint::u40;
+use heap_size::HeapSize;
+use leaf_layout::ElementLayout;
+
+pub type DataType = u40;
+
+/// every SAMPLE_RATE-th one and zero of the high bits is sampled
+const SAMPLE_RATE: usize = 256;
+
+/// floor(log(universe / len)), this minimizes low bits + high bits
+fn low_bits(len: usize, universe: u64) -> usize {
+    if universe > len as u64 && len > 0 {
+        63 - (universe / len as u64).leading_zeros() as usize
+    } else {
+        0
+    }
+}
+
+pub struct EliasFano {
+    len: usize,
+    low_bits: usize,
+    // number of different high parts up to the largest element
+    buckets: usize,
+    // low bits of all elements packed one after another, with one word padding
+    low: Vec<u64>,
+    // element i sets bit (element >> low_bits) + i, bucket h ends with the h-th zero
+    high: Vec<u64>,
+    // position of the one of every SAMPLE_RATE-th element
+    select_ones: Vec<usize>,
+    // position of every SAMPLE_RATE-th zero
+    select_zeros: Vec<usize>,
+}
+
+impl EliasFano {
+    ///elements must be sorted ascending!
+    pub fn new(elements: &[DataType]) -> EliasFano {
+        let len = elements.len();
+        let universe = elements.last().map_or(0, |max| u64::from(*max)) + 1;
+        let low_bits = low_bits(len, universe);
+        let buckets = (universe >> low_bits) as usize + 1;
+        let high_len = len + buckets;
+
+        let mut low = vec![0u64; (len * low_bits + 63) / 64 + 1];
+        let mut high = vec![0u64; (high_len + 63) / 64];
+        let mut select_ones = Vec::with_capacity(len / SAMPLE_RATE + 1);
+        let low_mask = (1u64 << low_bits) - 1;
+        for (i, element) in elements.iter().enumerate() {
+            let value = u64::from(*element);
+            if low_bits > 0 {
+                let offset = i * low_bits;
+                low[offset / 64] |= (value & low_mask) << (offset % 64);
+                if offset % 64 + low_bits > 64 {
+                    low[offset / 64 + 1] |= (value & low_mask) >> (64 - offset % 64);
+                }
+            }
+            let position = (value >> low_bits) as usize + i;
+            high[position / 64] |= 1u64 << (position % 64);
+            if i % SAMPLE_RATE == 0 {
+                select_ones.push(position);
+            }
+        }
+
+        let mut select_zeros = Vec::with_capacity(buckets / SAMPLE_RATE + 1);
+        let mut zeros = 0;
+        for position in 0..high_len {
+            if high[position / 64] & (1u64 << (position % 64)) == 0 {
+                if zeros % SAMPLE_RATE == 0 {
+                    select_zeros.push(position);
+                }
+                zeros += 1;
+            }
+        }
+        EliasFano { len, low_bits, buckets, low, high, select_ones, select_zeros }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    #[inline]
+    fn low(&self, i: usize) -> u64 {
+        if self.low_bits == 0 {
+            return 0;
+        }
+        let offset = i * self.low_bits;
+        let mask = (1u64 << self.low_bits) - 1;
+        unsafe {
+            let word = *self.low.get_unchecked(offset / 64) >> (offset % 64);
+            if offset % 64 + self.low_bits > 64 {
+                (word | *self.low.get_unchecked(offset / 64 + 1) << (64 - offset % 64)) & mask
+            } else {
+                word & mask
+            }
+        }
+    }
+
+    /// position of the rank-th set bit of the (possibly inverted) high bits, starting the scan at a sampled position
+    #[inline]
+    fn select_bit(&self, rank: usize, samples: &Vec<usize>, ones: bool) -> usize {
+        let flip = if ones { 0 } else { !0u64 };
+        let start = unsafe { *samples.get_unchecked(rank / SAMPLE_RATE) };
+        let mut remaining = rank % SAMPLE_RATE;
+        let mut word_index = start / 64;
+        let mut word = unsafe { (*self.high.get_unchecked(word_index) ^ flip) & (!0u64 << (start % 64)) };
+        loop {
+            let count = word.count_ones() as usize;
+            if remaining < count {
+                for _ in 0..remaining {
+                    word &= word - 1;
+                }
+                return word_index * 64 + word.trailing_zeros() as usize;
+            }
+            remaining -= count;
+            word_index += 1;
+            word = unsafe { *self.high.get_unchecked(word_index) ^ flip };
+        }
+    }
+
+    /// element at sorted position i
+    #[inline]
+    pub fn select(&self, i: usize) -> DataType {
+        debug_assert!(i < self.len);
+        let high = (self.select_bit(i, &self.select_ones, true) - i) as u64;
+        DataType::from(high << self.low_bits | self.low(i))
+    }
+
+    /// number of elements smaller than query
+    pub fn rank(&self, query: DataType) -> usize {
+        self.rank_in(query, 0, self.len)
+    }
+
+    /// number of elements smaller than query, that is known to be in left..right + 1
+    /// binary search over the positions of the bucket of query, that are in this range
+    #[inline]
+    pub fn rank_in(&self, query: DataType, left: usize, right: usize) -> usize {
+        let query = u64::from(query);
+        let bucket = (query >> self.low_bits) as usize;
+        if bucket >= self.buckets {
+            return self.len;
+        }
+        // elements of bucket h are the ones between the (h - 1)-th and the h-th zero
+        let bucket_start = if bucket == 0 { 0 } else { self.select_bit(bucket - 1, &self.select_zeros, false) + 1 - bucket };
+        let bucket_end = self.select_bit(bucket, &self.select_zeros, false) - bucket;
+        let query_low = query & ((1u64 << self.low_bits) - 1);
+        let mut range = (left.max(bucket_start), right.min(bucket_end));
+        debug_assert!(range.0 <= range.1);
+        //elements of the bucket have the same high bits, so they are compared by their low bits
+        while range.0 < range.1 {
+            let middle = (range.0 + range.1) / 2;
+            if self.low(middle) < query_low {
+                range = (middle + 1, range.1);
+            } else {
+                range = (range.0, middle);
+            }
+        }
+        range.0
+    }
+
+    /// largest element smaller than query
+    pub fn predecessor(&self, query: DataType) -> Option<DataType> {
+        match self.rank(query) {
+            0 => None,
+            rank => Some(self.select(rank - 1)),
+        }
+    }
+
+    pub fn heap_size(&self) -> usize {
+        self.low.heap_size() + self.high.heap_size() + (self.select_ones.capacity() + self.select_zeros.capacity()) * size_of::<usize>()
+    }
+}
+
+impl ElementLayout for EliasFano {
+    fn from_sorted(elements: Vec<DataType>, _group_size: usize) -> Self {
+        EliasFano::new(&elements)
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+    #[inline]
+    fn get(&self, pos: usize) -> DataType {
+        self.select(pos)
+    }
+    #[inline]
+    fn lower_bound(&self, query: DataType, left: usize, right: usize) -> usize {
+        self.rank_in(query, left, right)
+    }
+    fn heap_size(&self) -> usize {
+        EliasFano::heap_size(self)
+    }
+    /// low bits, high bits and samples (the same calculation as in new)
+    fn estimate_heap_size(len: usize, max: DataType, _group_size: usize) -> usize {
+        let universe = u64::from(max) + 1;
+        let low_bits = low_bits(len, universe);
+        let buckets = (universe >> low_bits) as usize + 1;
+        ((len * low_bits + 63) / 64 + 1 + (len + buckets + 63) / 64) * size_of::<u64>()
+            + (len / SAMPLE_RATE + buckets / SAMPLE_RATE + 2) * size_of::<usize>()
+    }
+}
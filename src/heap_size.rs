@@ -0,0 +1,147 @@
+extern crate boomphf;
+extern crate hashbrown;
+extern crate im_rc;
+
+/// this module contains the heap size accounting of the tries
+/// sizes are computed from the capacity of the containers, so unused capacity and the overhead of hash tables are included
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+use std::mem::size_of;
+use self::boomphf::hashmap::BoomHashMap;
+use uint::u40;
+use log::Log;
+
+/// bytes allocated on the heap by a value (its inline size is not included)
+pub trait HeapSize {
+    /// values without heap allocations can use the default
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl HeapSize for () {}
+
+impl HeapSize for u16 {}
+
+impl HeapSize for u32 {}
+
+impl HeapSize for u64 {}
+
+impl HeapSize for usize {}
+
+impl HeapSize for u40 {}
+
+impl<A: HeapSize, B: HeapSize> HeapSize for (A, B) {
+    fn heap_size(&self) -> usize {
+        self.0.heap_size() + self.1.heap_size()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(|value| value.heap_size()).sum::<usize>()
+    }
+}
+
+/// heap size of std hash maps and hashbrown (swiss table with one control byte per bucket)
+pub fn swiss_table_size(capacity: usize, entry_size: usize) -> usize {
+    if capacity == 0 {
+        return 0;
+    }
+    let buckets = if capacity < 8 {
+        (capacity + 1).next_power_of_two()
+    } else {
+        (capacity * 8 / 7).next_power_of_two()
+    };
+    // 16 = control bytes for sse group width
+    buckets * (entry_size + 1) + 16
+}
+
+/// estimated heap size of a boomphf map: keys + values + about 3 bits per key for the mphf (gamma = 1.7)
+pub fn boomphf_size(len: usize, entry_size: usize) -> usize {
+    len * entry_size + len * 3 / 8
+}
+
+/// std hash map, FnvHashMap and FxHashMap
+impl<K: HeapSize + Eq + Hash, V: HeapSize, S: BuildHasher> HeapSize for HashMap<K, V, S> {
+    fn heap_size(&self) -> usize {
+        swiss_table_size(self.capacity(), size_of::<(K, V)>()) + self.iter().map(|(key, value)| key.heap_size() + value.heap_size()).sum::<usize>()
+    }
+}
+
+impl<K: HeapSize + Eq + Hash, V: HeapSize, S: BuildHasher> HeapSize for hashbrown::HashMap<K, V, S> {
+    fn heap_size(&self) -> usize {
+        swiss_table_size(self.capacity(), size_of::<(K, V)>()) + self.iter().map(|(key, value)| key.heap_size() + value.heap_size()).sum::<usize>()
+    }
+}
+
+/// estimated: im-rc stores entries in a hash array mapped trie with 64 slot nodes that are about half filled
+impl<K: HeapSize + Eq + Hash + Clone, V: HeapSize + Clone> HeapSize for im_rc::HashMap<K, V> {
+    fn heap_size(&self) -> usize {
+        self.len() * (size_of::<(K, V)>() + size_of::<usize>()) * 2 + self.iter().map(|(key, value)| key.heap_size() + value.heap_size()).sum::<usize>()
+    }
+}
+
+/// keys and values of the tries never allocate, so only the table is counted
+impl<K: Clone + Hash + Debug + PartialEq, V: Debug> HeapSize for BoomHashMap<K, V> {
+    fn heap_size(&self) -> usize {
+        boomphf_size(self.len(), size_of::<K>() + size_of::<V>())
+    }
+}
+
+/// tries, whose heap size is split into their components
+pub trait TrieMemory {
+    ///bytes on the heap per component, including unused capacity and hash table overhead
+    fn memory_breakdown(&self) -> MemoryBreakdown;
+
+    ///bytes on the heap
+    fn heap_size(&self) -> usize {
+        self.memory_breakdown().total()
+    }
+}
+
+/// heap size of the components of a trie
+pub struct MemoryBreakdown {
+    pub lss_top: usize,
+    pub lss_leaf: usize,
+    // one entry per branch level, lowest first
+    pub lss_branch: Vec<usize>,
+    // elements stored outside of lss_leaf
+    pub elements: usize,
+    // tables of the level search (trained search tree)
+    pub level_search: usize,
+    // true if a level uses a table, whose layout is not revealed (boomphf, im-rc, mphf)
+    pub estimated: bool,
+}
+
+impl MemoryBreakdown {
+    /// breakdown of a trie, whose components are exactly measured by HeapSize and that has no level search tables
+    /// components, that a trie does not have, are passed as ()
+    pub fn of<T: HeapSize, L: HeapSize, B: HeapSize, E: HeapSize>(lss_top: &T, lss_leaf: &L, lss_branch: &[B], elements: &E) -> MemoryBreakdown {
+        MemoryBreakdown {
+            lss_top: lss_top.heap_size(),
+            lss_leaf: lss_leaf.heap_size(),
+            lss_branch: lss_branch.iter().map(|level| level.heap_size()).collect(),
+            elements: elements.heap_size(),
+            level_search: 0,
+            estimated: false,
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.lss_top + self.lss_leaf + self.lss_branch.iter().sum::<usize>() + self.elements + self.level_search
+    }
+
+    pub fn print(&self, log: &Log) {
+        log.print_result(format!("memory=lss_top\tbytes={}", self.lss_top));
+        log.print_result(format!("memory=lss_leaf\tbytes={}", self.lss_leaf));
+        for (level, bytes) in self.lss_branch.iter().enumerate() {
+            log.print_result(format!("memory=lss_branch\tlevel={}\tbytes={}", level + 1, bytes));
+        }
+        log.print_result(format!("memory=elements\tbytes={}", self.elements));
+        log.print_result(format!("memory=level_search\tbytes={}", self.level_search));
+        log.print_result(format!("memory=total\tbytes={}\testimated={}", self.total(), self.estimated));
+    }
+}
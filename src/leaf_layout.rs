@@ -0,0 +1,428 @@
+/// this module contains layouts of the element array of the static tries
+/// the trie narrows a query down to a range of sorted positions, the layout searches the predecessor in this range
+/// sorted = plain sorted array with binary search (one cache miss per step on large groups)
+/// eytzinger = every leaf group is stored in bfs order of a complete binary search tree, the first levels share cache lines
+/// blocks = sorted array with a separator (first key) per block of 8 or 16 keys, keys of a block are compared without branches
+/// interpolation = sorted array, positions are interpolated between the bounds of the range
+/// linear model = sorted array with a least squares line per leaf group, that predicts the position up to a stored maximal error
+
+use std::cell::Cell;
+use std::mem::size_of;
+use uint::u40;
+use heap_size::HeapSize;
+use log::Log;
+
+pub type DataType = u40;
+
+/// element array of a static trie, positions are always the positions in sorted order
+pub trait ElementLayout {
+    /// elements must be sorted ascending, group_size is the size of the leaf groups (a power of two)
+    fn from_sorted(elements: Vec<DataType>, group_size: usize) -> Self where Self: Sized;
+    fn len(&self) -> usize;
+    /// element at sorted position pos
+    fn get(&self, pos: usize) -> DataType;
+    /// first position in left..right, whose element is not smaller than query (right, if there is none)
+    /// elements before left are smaller and elements from right on are not smaller than query
+    fn lower_bound(&self, query: DataType, left: usize, right: usize) -> usize;
+    fn heap_size(&self) -> usize;
+    /// predicted heap size for len elements up to max in groups of group_size (used to fit a trie into a memory budget)
+    fn estimate_heap_size(len: usize, _max: DataType, _group_size: usize) -> usize where Self: Sized {
+        len * size_of::<DataType>()
+    }
+    /// layouts with a model print its statistics
+    fn print_stats(&self, _log: &Log) {}
+}
+
+/// sorted array with binary search (layout of all tries without own layout)
+pub struct SortedLayout {
+    elements: Vec<DataType>,
+}
+
+impl ElementLayout for SortedLayout {
+    fn from_sorted(elements: Vec<DataType>, _group_size: usize) -> Self {
+        SortedLayout { elements }
+    }
+    fn len(&self) -> usize {
+        self.elements.len()
+    }
+    #[inline]
+    fn get(&self, pos: usize) -> DataType {
+        unsafe { *self.elements.get_unchecked(pos) }
+    }
+    #[inline]
+    fn lower_bound(&self, query: DataType, left: usize, right: usize) -> usize {
+        match self.elements.get(left..right).unwrap().binary_search(&query) {
+            Ok(pos) => pos + left,
+            Err(pos) => pos + left
+        }
+    }
+    fn heap_size(&self) -> usize {
+        self.elements.heap_size()
+    }
+}
+
+/// every group of group_size elements in eytzinger (bfs) order
+/// node k (starting with 1) of a group has its children at 2k and 2k + 1
+pub struct EytzingerLayout {
+    // groups one after another, node k of a group is at group start + k - 1
+    data: Vec<DataType>,
+    group_size: usize,
+    // sorted position in group -> node - 1, for full groups and the last (smaller) group
+    to_node: Vec<u32>,
+    last_to_node: Vec<u32>,
+    // node - 1 -> sorted position in group
+    to_sorted: Vec<u32>,
+    last_to_sorted: Vec<u32>,
+}
+
+impl EytzingerLayout {
+    /// (sorted position -> node - 1, node - 1 -> sorted position) for a tree with len nodes
+    fn permutation(len: usize) -> (Vec<u32>, Vec<u32>) {
+        let mut to_sorted = vec![0u32; len];
+        //in order traversal visits the nodes in sorted order
+        let mut stack = Vec::new();
+        let mut node = 1;
+        let mut next = 0;
+        while node <= len || !stack.is_empty() {
+            if node <= len {
+                stack.push(node);
+                node *= 2;
+            } else {
+                node = stack.pop().unwrap();
+                to_sorted[node - 1] = next as u32;
+                next += 1;
+                node = node * 2 + 1;
+            }
+        }
+        let mut to_node = vec![0u32; len];
+        for (node, sorted) in to_sorted.iter().enumerate() {
+            to_node[*sorted as usize] = node as u32;
+        }
+        (to_node, to_sorted)
+    }
+
+    #[inline]
+    fn group_len(&self, group: usize) -> usize {
+        (self.data.len() - group * self.group_size).min(self.group_size)
+    }
+
+    /// lower bound in one group, relative to the group start
+    #[inline]
+    fn group_lower_bound(&self, group: usize, query: DataType) -> usize {
+        let len = self.group_len(group);
+        let start = group * self.group_size;
+        let mut node = 1;
+        unsafe {
+            while node <= len {
+                node = 2 * node + (*self.data.get_unchecked(start + node - 1) < query) as usize;
+            }
+        }
+        //remove the right turns after the last left turn, the remaining node is the lower bound
+        node >>= (!node).trailing_zeros() + 1;
+        if node == 0 {
+            return len;
+        }
+        let to_sorted = if len == self.group_size { &self.to_sorted } else { &self.last_to_sorted };
+        unsafe { *to_sorted.get_unchecked(node - 1) as usize }
+    }
+}
+
+impl ElementLayout for EytzingerLayout {
+    fn from_sorted(elements: Vec<DataType>, group_size: usize) -> Self {
+        assert!(group_size.is_power_of_two());
+        let (to_node, to_sorted) = EytzingerLayout::permutation(group_size.min(elements.len()));
+        let last_len = elements.len() % group_size;
+        let (last_to_node, last_to_sorted) = EytzingerLayout::permutation(last_len);
+        let mut data = vec![DataType::from(0u64); elements.len()];
+        for (group, chunk) in elements.chunks(group_size).enumerate() {
+            let to_node = if chunk.len() == group_size { &to_node } else { &last_to_node };
+            for (sorted, element) in chunk.iter().enumerate() {
+                data[group * group_size + to_node[sorted] as usize] = *element;
+            }
+        }
+        EytzingerLayout { data, group_size, to_node, last_to_node, to_sorted, last_to_sorted }
+    }
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+    #[inline]
+    fn get(&self, pos: usize) -> DataType {
+        let group = pos / self.group_size;
+        let offset = pos % self.group_size;
+        let to_node = if self.group_len(group) == self.group_size { &self.to_node } else { &self.last_to_node };
+        unsafe { *self.data.get_unchecked(group * self.group_size + *to_node.get_unchecked(offset) as usize) }
+    }
+    #[inline]
+    fn lower_bound(&self, query: DataType, left: usize, right: usize) -> usize {
+        if left >= right {
+            return left;
+        }
+        //last group, whose minimum is smaller than query (groups are sorted among each other)
+        let mut group = left / self.group_size;
+        if self.get(group * self.group_size) >= query {
+            return left;
+        }
+        let last_group = (right - 1) / self.group_size;
+        while group < last_group && self.get((group + 1) * self.group_size) < query {
+            group += 1;
+        }
+        let pos = group * self.group_size + self.group_lower_bound(group, query);
+        pos.max(left).min(right)
+    }
+    fn heap_size(&self) -> usize {
+        self.data.heap_size() + (self.to_node.capacity() + self.last_to_node.capacity() + self.to_sorted.capacity() + self.last_to_sorted.capacity()) * size_of::<u32>()
+    }
+}
+
+/// number of keys per block of BlockLayout
+pub trait BlockKeys {
+    const LEN: usize;
+}
+
+pub struct Keys8;
+
+impl BlockKeys for Keys8 {
+    const LEN: usize = 8;
+}
+
+pub struct Keys16;
+
+impl BlockKeys for Keys16 {
+    const LEN: usize = 16;
+}
+
+/// sorted array in blocks of B::LEN keys with the first key of every block as separator
+/// separators are searched with a branch free binary search, keys of the found block are counted without branches
+pub struct BlockLayout<B: BlockKeys> {
+    // padded with max_value to whole blocks
+    elements: Vec<DataType>,
+    separators: Vec<DataType>,
+    len: usize,
+    block_keys: std::marker::PhantomData<B>,
+}
+
+impl<B: BlockKeys> ElementLayout for BlockLayout<B> {
+    fn from_sorted(mut elements: Vec<DataType>, _group_size: usize) -> Self {
+        let len = elements.len();
+        let blocks = (len + B::LEN - 1) / B::LEN;
+        // padding is never smaller than a query
+        elements.resize(blocks * B::LEN, DataType::max_value());
+        let separators = elements.iter().step_by(B::LEN).cloned().collect();
+        BlockLayout { elements, separators, len, block_keys: std::marker::PhantomData }
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+    #[inline]
+    fn get(&self, pos: usize) -> DataType {
+        unsafe { *self.elements.get_unchecked(pos) }
+    }
+    #[inline]
+    fn lower_bound(&self, query: DataType, left: usize, right: usize) -> usize {
+        if left >= right {
+            return left;
+        }
+        let first_block = left / B::LEN;
+        unsafe {
+            if *self.separators.get_unchecked(first_block) >= query {
+                return left;
+            }
+            //last block in range, whose separator is smaller than query
+            let mut block = first_block;
+            let mut len = (right - 1) / B::LEN - first_block + 1;
+            while len > 1 {
+                let half = len / 2;
+                block = if *self.separators.get_unchecked(block + half) < query { block + half } else { block };
+                len -= half;
+            }
+            let start = block * B::LEN;
+            let mut count = 0;
+            for key in self.elements.get_unchecked(start..start + B::LEN) {
+                count += (*key < query) as usize;
+            }
+            (start + count).max(left).min(right)
+        }
+    }
+    fn heap_size(&self) -> usize {
+        self.elements.heap_size() + self.separators.heap_size()
+    }
+}
+
+/// ranges up to this length are scanned instead of interpolated
+const SCAN_THRESHOLD: usize = 8;
+
+/// sorted array with interpolation search
+pub struct InterpolationLayout {
+    elements: Vec<DataType>,
+}
+
+impl ElementLayout for InterpolationLayout {
+    fn from_sorted(elements: Vec<DataType>, _group_size: usize) -> Self {
+        InterpolationLayout { elements }
+    }
+    fn len(&self) -> usize {
+        self.elements.len()
+    }
+    #[inline]
+    fn get(&self, pos: usize) -> DataType {
+        unsafe { *self.elements.get_unchecked(pos) }
+    }
+    #[inline]
+    fn lower_bound(&self, query: DataType, mut left: usize, mut right: usize) -> usize {
+        //elements before left are smaller and elements from right on are not smaller than query
+        while right - left > SCAN_THRESHOLD {
+            let low = self.get(left);
+            let high = self.get(right - 1);
+            if query <= low {
+                return left;
+            }
+            if query > high {
+                return right;
+            }
+            let fraction = (u64::from(query) - u64::from(low)) as f64 / (u64::from(high) - u64::from(low)) as f64;
+            let pos = (left + (fraction * (right - 1 - left) as f64) as usize).min(right - 1);
+            if self.get(pos) < query {
+                left = pos + 1;
+            } else {
+                right = pos;
+            }
+        }
+        while left < right && self.get(left) < query {
+            left += 1;
+        }
+        left
+    }
+    fn heap_size(&self) -> usize {
+        self.elements.heap_size()
+    }
+}
+
+/// least squares line of one leaf group: position in group = intercept + slope * (element - first element)
+struct LinearModel {
+    intercept: f64,
+    slope: f64,
+    // maximal difference between predicted and real position of the elements
+    max_error: u32,
+}
+
+impl LinearModel {
+    fn fit(group: &[DataType]) -> LinearModel {
+        let first = u64::from(group[0]);
+        let len = group.len() as f64;
+        let mean_x = group.iter().map(|v| (u64::from(*v) - first) as f64).sum::<f64>() / len;
+        let mean_y = (len - 1.) / 2.;
+        let mut covariance = 0.;
+        let mut variance = 0.;
+        for (pos, value) in group.iter().enumerate() {
+            let x = (u64::from(*value) - first) as f64 - mean_x;
+            covariance += x * (pos as f64 - mean_y);
+            variance += x * x;
+        }
+        let slope = if variance > 0. { covariance / variance } else { 0. };
+        let mut model = LinearModel { intercept: mean_y - slope * mean_x, slope, max_error: 0 };
+        for (pos, value) in group.iter().enumerate() {
+            let error = (model.predict(*value, first, group.len()) as i64 - pos as i64).abs() as u32;
+            model.max_error = model.max_error.max(error);
+        }
+        model
+    }
+
+    /// predicted position in a group of len elements
+    #[inline]
+    fn predict(&self, value: DataType, first: u64, len: usize) -> usize {
+        let pos = self.intercept + self.slope * (u64::from(value).saturating_sub(first)) as f64;
+        if pos <= 0. {
+            0
+        } else {
+            (pos as usize).min(len)
+        }
+    }
+}
+
+/// sorted array with a linear model per leaf group
+/// the model predicts a position, the lower bound is searched in the window of the maximal error around it
+pub struct LinearModelLayout {
+    elements: Vec<DataType>,
+    group_size: usize,
+    models: Vec<LinearModel>,
+    // sum of the absolute errors of all elements
+    error_sum: usize,
+    // queries, whose lower bound was outside of the window
+    fallbacks: Cell<usize>,
+}
+
+impl LinearModelLayout {
+    /// lower bound in one group, relative to the group start
+    #[inline]
+    fn group_lower_bound(&self, group: usize, query: DataType) -> usize {
+        let start = group * self.group_size;
+        let elements = unsafe { self.elements.get_unchecked(start..(start + self.group_size).min(self.elements.len())) };
+        let model = unsafe { self.models.get_unchecked(group) };
+        let predicted = model.predict(query, u64::from(elements[0]), elements.len());
+        //a query between two elements is predicted between their positions, so one more position is searched
+        let error = model.max_error as usize + 1;
+        let left = predicted.saturating_sub(error);
+        let right = (predicted + error + 1).min(elements.len());
+        if (left > 0 && elements[left - 1] >= query) || (right < elements.len() && elements[right] < query) {
+            //rounding of the model, should not happen
+            self.fallbacks.set(self.fallbacks.get() + 1);
+            return match elements.binary_search(&query) {
+                Ok(pos) => pos,
+                Err(pos) => pos
+            };
+        }
+        match elements[left..right].binary_search(&query) {
+            Ok(pos) => pos + left,
+            Err(pos) => pos + left
+        }
+    }
+}
+
+impl ElementLayout for LinearModelLayout {
+    fn from_sorted(elements: Vec<DataType>, group_size: usize) -> Self {
+        let models: Vec<LinearModel> = elements.chunks(group_size).map(|group| LinearModel::fit(group)).collect();
+        let mut error_sum = 0;
+        for (group, model) in elements.chunks(group_size).zip(models.iter()) {
+            for (pos, value) in group.iter().enumerate() {
+                error_sum += (model.predict(*value, u64::from(group[0]), group.len()) as i64 - pos as i64).abs() as usize;
+            }
+        }
+        LinearModelLayout { elements, group_size, models, error_sum, fallbacks: Cell::new(0) }
+    }
+    fn len(&self) -> usize {
+        self.elements.len()
+    }
+    #[inline]
+    fn get(&self, pos: usize) -> DataType {
+        unsafe { *self.elements.get_unchecked(pos) }
+    }
+    #[inline]
+    fn lower_bound(&self, query: DataType, left: usize, right: usize) -> usize {
+        if left >= right {
+            return left;
+        }
+        //last group, whose minimum is smaller than query (groups are sorted among each other)
+        let mut group = left / self.group_size;
+        if self.get(group * self.group_size) >= query {
+            return left;
+        }
+        let last_group = (right - 1) / self.group_size;
+        while group < last_group && self.get((group + 1) * self.group_size) < query {
+            group += 1;
+        }
+        let pos = group * self.group_size + self.group_lower_bound(group, query);
+        pos.max(left).min(right)
+    }
+    fn heap_size(&self) -> usize {
+        self.elements.heap_size() + self.models.capacity() * size_of::<LinearModel>()
+    }
+    fn estimate_heap_size(len: usize, _max: DataType, group_size: usize) -> usize {
+        len * size_of::<DataType>() + (len + group_size - 1) / group_size * size_of::<LinearModel>()
+    }
+    fn print_stats(&self, log: &Log) {
+        let max_error = self.models.iter().map(|model| model.max_error).max().unwrap_or(0);
+        let mean_max_error = self.models.iter().map(|model| model.max_error as f64).sum::<f64>() / self.models.len().max(1) as f64;
+        log.print_result(format!("layout=linear_model\tgroups={}\tmax_error={}\tmean_max_error={}\tmean_error={}\tfallbacks={}",
+                                 self.models.len(), max_error, mean_max_error, self.error_sum as f64 / self.elements.len().max(1) as f64, self.fallbacks.get()));
+    }
+}
@@ -0,0 +1,46 @@
+/// this module contains the keys of the nodes of a binary trie over 40 or 64 bit values
+/// the node of a value in level l (counted from the leaf level start_level) is the value without its lowest l + start_level bits
+
+use uint::u40;
+
+pub type DataType = u40;
+
+/// values, whose prefixes are the keys of the trie nodes
+pub trait PathValue: Copy {
+    /// value without its lowest bits
+    fn cut(self, bits: usize) -> Self;
+    fn is_even(self) -> bool;
+}
+
+impl PathValue for u40 {
+    #[inline]
+    fn cut(self, bits: usize) -> u40 {
+        self >> u40::from(bits)
+    }
+    #[inline]
+    fn is_even(self) -> bool {
+        self % 2 == 0
+    }
+}
+
+impl PathValue for usize {
+    #[inline]
+    fn cut(self, bits: usize) -> usize {
+        self >> bits
+    }
+    #[inline]
+    fn is_even(self) -> bool {
+        self % 2 == 0
+    }
+}
+
+#[inline]
+pub fn calc_path<T: PathValue>(position: T, lss_level: usize, start_level: usize) -> T {
+    position.cut(lss_level + start_level)
+}
+
+//returns if the node is the left of its parent
+#[inline]
+pub fn is_left_child<T: PathValue>(path: T) -> bool {
+    path.is_even()
+}
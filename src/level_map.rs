@@ -0,0 +1,209 @@
+extern crate boomphf;
+extern crate hashbrown;
+extern crate im_rc;
+
+/// this module contains the interface of the hash tables, that are used for the lss levels of the tries
+/// and adapters for all hash tables, that are used in this crate
+/// a new hash table can be used in yft40so_generic_binsearch and yft40_generic_hash by implementing LevelMap for it
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::hash::{BuildHasher, Hash};
+use std::mem::size_of;
+use self::boomphf::hashmap::BoomHashMap;
+use uint::u40;
+use args::Args;
+use heap_size::{swiss_table_size, boomphf_size};
+
+/// map from node path (prefix of a key) to node data for one trie level
+pub trait LevelMap<K, V> {
+    /// keys must be sorted ascending and unique, values[i] belongs to keys[i]
+    fn from_sorted_pairs(keys: Vec<K>, values: Vec<V>) -> Self where Self: Sized;
+    /// like from_sorted_pairs, tables with parameters (e.g. a fixed size) read them from config
+    fn from_sorted_pairs_with_config(keys: Vec<K>, values: Vec<V>, _config: &LevelMapConfig) -> Self where Self: Sized {
+        Self::from_sorted_pairs(keys, values)
+    }
+    fn get(&self, key: &K) -> Option<&V>;
+    fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+    /// number of stored nodes
+    fn len(&self) -> usize;
+    /// bytes allocated on the heap (estimated, if the table doesn't reveal its layout)
+    fn heap_size(&self) -> usize;
+    /// true if heap_size is estimated
+    fn heap_size_is_estimated(&self) -> bool {
+        false
+    }
+    /// predicted heap_size of a table with len keys, that is built with from_sorted_pairs_with_config
+    fn estimate_heap_size(len: usize, config: &LevelMapConfig) -> usize where Self: Sized;
+    /// like get, but counts the probes and the outcome of approximate pre checks in stats
+    /// by default a lookup is one probe of the table
+    fn get_with_stats(&self, key: &K, stats: &mut LookupStats) -> Option<&V> {
+        stats.probes += 1;
+        self.get(key)
+    }
+}
+
+/// probes and outcome of the approximate pre checks of lookups with get_with_stats
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LookupStats {
+    /// number of accessed table slots, fingerprints and filter blocks
+    pub probes: usize,
+    /// number of lookups, that found a false candidate
+    pub false_positives: usize,
+    /// number of lookups, that were answered by an approximate pre check without asking the table
+    pub avoided_lookups: usize,
+}
+
+/// parameters of the level maps, tables ignore the ones they don't use
+pub struct LevelMapConfig {
+    /// maximal ratio of used slots in tables with fixed size (between 0 and 1)
+    pub load_factor: f64,
+    /// bits per key for fingerprints
+    pub fingerprint_bits: usize,
+    /// bits per key for bloom filters
+    pub filter_bits: usize,
+}
+
+impl LevelMapConfig {
+    pub fn from_args(args: &Args) -> LevelMapConfig {
+        LevelMapConfig { load_factor: args.level_load_factor as f64 / 100., fingerprint_bits: args.fingerprint_bits, filter_bits: args.filter_bits }
+    }
+}
+
+impl Default for LevelMapConfig {
+    fn default() -> LevelMapConfig {
+        LevelMapConfig { load_factor: 0.75, fingerprint_bits: 8, filter_bits: 10 }
+    }
+}
+
+/// std hash map, FnvHashMap and FxHashMap
+impl<K: Eq + Hash, V, S: BuildHasher + Default> LevelMap<K, V> for HashMap<K, V, S> {
+    fn from_sorted_pairs(keys: Vec<K>, values: Vec<V>) -> Self {
+        debug_assert!(keys.len() == values.len());
+        let mut map = HashMap::with_capacity_and_hasher(keys.len(), S::default());
+        for (key, value) in keys.into_iter().zip(values.into_iter()) {
+            map.insert(key, value);
+        }
+        map
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+    fn contains_key(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+    fn len(&self) -> usize {
+        self.len()
+    }
+    fn heap_size(&self) -> usize {
+        swiss_table_size(self.capacity(), size_of::<(K, V)>())
+    }
+    fn estimate_heap_size(len: usize, _config: &LevelMapConfig) -> usize {
+        swiss_table_size(len, size_of::<(K, V)>())
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> LevelMap<K, V> for hashbrown::HashMap<K, V, S> {
+    fn from_sorted_pairs(keys: Vec<K>, values: Vec<V>) -> Self {
+        debug_assert!(keys.len() == values.len());
+        let mut map = hashbrown::HashMap::with_capacity_and_hasher(keys.len(), S::default());
+        for (key, value) in keys.into_iter().zip(values.into_iter()) {
+            map.insert(key, value);
+        }
+        map
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+    fn contains_key(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+    fn len(&self) -> usize {
+        self.len()
+    }
+    fn heap_size(&self) -> usize {
+        swiss_table_size(self.capacity(), size_of::<(K, V)>())
+    }
+    fn estimate_heap_size(len: usize, _config: &LevelMapConfig) -> usize {
+        swiss_table_size(len, size_of::<(K, V)>())
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> LevelMap<K, V> for im_rc::HashMap<K, V> {
+    fn from_sorted_pairs(keys: Vec<K>, values: Vec<V>) -> Self {
+        debug_assert!(keys.len() == values.len());
+        let mut map = im_rc::HashMap::new();
+        for (key, value) in keys.into_iter().zip(values.into_iter()) {
+            map.insert(key, value);
+        }
+        map
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+    fn contains_key(&self, key: &K) -> bool {
+        self.contains_key(key)
+    }
+    fn len(&self) -> usize {
+        self.len()
+    }
+    /// estimated: im-rc stores entries in a hash array mapped trie with 64 slot nodes that are about half filled
+    fn heap_size(&self) -> usize {
+        self.len() * (size_of::<(K, V)>() + size_of::<usize>()) * 2
+    }
+    fn heap_size_is_estimated(&self) -> bool {
+        true
+    }
+    fn estimate_heap_size(len: usize, _config: &LevelMapConfig) -> usize {
+        len * (size_of::<(K, V)>() + size_of::<usize>()) * 2
+    }
+}
+
+impl<K: Clone + Hash + Debug + Eq, V: Debug> LevelMap<K, V> for BoomHashMap<K, V> {
+    fn from_sorted_pairs(keys: Vec<K>, values: Vec<V>) -> Self {
+        BoomHashMap::new(keys, values)
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        self.get(key)
+    }
+    fn len(&self) -> usize {
+        self.len()
+    }
+    /// estimated: the mphf is counted with about 3 bits per key
+    fn heap_size(&self) -> usize {
+        boomphf_size(self.len(), size_of::<K>() + size_of::<V>())
+    }
+    fn heap_size_is_estimated(&self) -> bool {
+        true
+    }
+    fn estimate_heap_size(len: usize, _config: &LevelMapConfig) -> usize {
+        boomphf_size(len, size_of::<K>() + size_of::<V>())
+    }
+}
+
+/// boomphf map, that is constructed with multiple threads
+pub struct ParallelBoomHashMap<K = u40, V = u40>(BoomHashMap<K, V>);
+
+impl<K: Clone + Hash + Debug + Eq + Send + Sync, V: Debug + Send + Sync> LevelMap<K, V> for ParallelBoomHashMap<K, V> {
+    fn from_sorted_pairs(keys: Vec<K>, values: Vec<V>) -> Self {
+        ParallelBoomHashMap(BoomHashMap::new_parallel(keys, values))
+    }
+    fn get(&self, key: &K) -> Option<&V> {
+        self.0.get(key)
+    }
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+    /// estimated like BoomHashMap
+    fn heap_size(&self) -> usize {
+        boomphf_size(self.0.len(), size_of::<K>() + size_of::<V>())
+    }
+    fn heap_size_is_estimated(&self) -> bool {
+        true
+    }
+    fn estimate_heap_size(len: usize, _config: &LevelMapConfig) -> usize {
+        boomphf_size(len, size_of::<K>() + size_of::<V>())
+    }
+}
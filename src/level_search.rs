@@ -0,0 +1,127 @@
+/// this module contains the strategies, that choose the level probed next by the level binary search of the static tries
+/// the search range (low, high) contains the lowest existing ancestor of the query, high == levels stands for lss_top
+/// a probe at level p either finds a node (next range (low, p)) or not (next range (p + 1, high))
+
+use log::Log;
+
+pub trait LevelSearch {
+    /// levels == number of lss levels, the exit levels are 0..levels + 1 (levels == lss_top)
+    fn new(levels: usize) -> Self where Self: Sized;
+    /// level to probe in the search range, low < high
+    fn position(&self, low: usize, high: usize) -> usize;
+    /// frequencies[x] is the number of searches, that ended in level x
+    fn train(&mut self, _frequencies: &[u64]) {}
+    fn heap_size(&self) -> usize {
+        0
+    }
+    /// heap_size of a search over levels lss levels
+    fn estimate_heap_size(_levels: usize) -> usize where Self: Sized {
+        0
+    }
+    fn print_stats(&self, _log: &Log) {}
+}
+
+/// binary search, that cuts the range in the middle (search of all tries without own search)
+pub struct MidpointSearch;
+
+impl LevelSearch for MidpointSearch {
+    fn new(_levels: usize) -> Self {
+        MidpointSearch
+    }
+    #[inline]
+    fn position(&self, low: usize, high: usize) -> usize {
+        (low + high) / 2
+    }
+}
+
+/// search tree over the levels, that minimizes the expected number of probes for the trained exit frequencies
+/// each exit level gets one extra count, so levels that were not seen in training don't end up arbitrarily deep
+pub struct TrainedSearch {
+    levels: usize,
+    // probed level of the range (low, high) at low * (levels + 1) + high, midpoints until trained
+    positions: Vec<u8>,
+    // (trained queries, probes per query of the midpoint search, probes per query of the trained search)
+    training: Option<(u64, f64, f64)>,
+}
+
+impl TrainedSearch {
+    /// expected number of probes per search of the current positions
+    fn expected_probes(&self, weights: &[f64]) -> f64 {
+        let total: f64 = weights.iter().sum();
+        self.range_probes(weights, 0, self.levels) / total
+    }
+
+    /// weighted number of probes of all searches ending in low..high + 1
+    fn range_probes(&self, weights: &[f64], low: usize, high: usize) -> f64 {
+        if low == high {
+            return 0.;
+        }
+        let position = self.position(low, high);
+        weights[low..high + 1].iter().sum::<f64>() + self.range_probes(weights, low, position) + self.range_probes(weights, position + 1, high)
+    }
+}
+
+impl LevelSearch for TrainedSearch {
+    fn new(levels: usize) -> Self {
+        let mut positions = vec![0; (levels + 1) * (levels + 1)];
+        for low in 0..levels + 1 {
+            for high in low + 1..levels + 1 {
+                positions[low * (levels + 1) + high] = ((low + high) / 2) as u8;
+            }
+        }
+        TrainedSearch { levels, positions, training: None }
+    }
+
+    #[inline]
+    fn position(&self, low: usize, high: usize) -> usize {
+        unsafe { *self.positions.get_unchecked(low * (self.levels + 1) + high) as usize }
+    }
+
+    /// optimal alphabetic tree by dynamic programming over all ranges, O(levels^3)
+    fn train(&mut self, frequencies: &[u64]) {
+        let n = self.levels + 1;
+        assert_eq!(frequencies.len(), n);
+        let weights: Vec<f64> = frequencies.iter().map(|frequency| *frequency as f64 + 1.).collect();
+        let midpoint_probes = self.expected_probes(&weights);
+        //prefix_sums[x] == weight of the exit levels before x
+        let mut prefix_sums = vec![0.; n + 1];
+        for x in 0..n {
+            prefix_sums[x + 1] = prefix_sums[x] + weights[x];
+        }
+        // cost[low * n + high] == weighted probes of the best tree for the range (low, high)
+        let mut cost = vec![0.; n * n];
+        for len in 1..n {
+            for low in 0..n - len {
+                let high = low + len;
+                let mut best = (std::f64::MAX, low);
+                for position in low..high {
+                    let candidate = cost[low * n + position] + cost[(position + 1) * n + high];
+                    if candidate < best.0 {
+                        best = (candidate, position);
+                    }
+                }
+                cost[low * n + high] = best.0 + prefix_sums[high + 1] - prefix_sums[low];
+                self.positions[low * n + high] = best.1 as u8;
+            }
+        }
+        let trained_probes = self.expected_probes(&weights);
+        self.training = Some((frequencies.iter().sum(), midpoint_probes, trained_probes));
+    }
+
+    fn heap_size(&self) -> usize {
+        self.positions.capacity()
+    }
+
+    fn estimate_heap_size(levels: usize) -> usize {
+        (levels + 1) * (levels + 1)
+    }
+
+    fn print_stats(&self, log: &Log) {
+        match self.training {
+            Some((queries, midpoint_probes, trained_probes)) => {
+                log.print_result(format!("level_search=trained\tqueries={}\tmidpoint_probes={}\ttrained_probes={}\tbytes={}", queries, midpoint_probes, trained_probes, self.heap_size()));
+            }
+            None => log.print_result(format!("level_search=untrained\tbytes={}", self.heap_size())),
+        }
+    }
+}
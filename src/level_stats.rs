@@ -0,0 +1,140 @@
+extern crate rand;
+
+/// this module estimates the number of trie nodes per level, which is used to select the leaf and top level of the tries
+/// scan = one pass over all elements per asked level (exact)
+/// histogram = one pass over all elements for all levels at once (exact)
+/// sample = randomly drawn pairs of neighbouring elements (approximate, with 95% confidence bounds)
+
+use self::rand::{Rng, SeedableRng};
+use self::rand::rngs::StdRng;
+use uint::u40;
+use args::{Args, LevelEstimator};
+use log::Log;
+
+pub type DataType = u40;
+
+/// number of prefix lengths of a 40 bit value (0 to 40 shifted bits)
+const LEVELS: usize = 41;
+/// z value of a two sided 95% confidence interval
+const Z_95: f64 = 1.96;
+
+pub struct LevelStats<'a> {
+    elements: &'a [DataType],
+    estimator: LevelEstimator,
+    // estimated nodes per level (unused by scan)
+    nodes: Vec<f64>,
+    // half width of the confidence interval per level (0 if exact)
+    margins: Vec<f64>,
+}
+
+impl<'a> LevelStats<'a> {
+    ///elements must be sorted ascending and not empty!
+    pub fn new(elements: &'a [DataType], args: &Args) -> LevelStats<'a> {
+        assert!(elements.len() > 0);
+        let mut stats = LevelStats { elements, estimator: args.level_estimator, nodes: Vec::new(), margins: vec![0.; LEVELS] };
+        match args.level_estimator {
+            LevelEstimator::Scan => {}
+            LevelEstimator::Histogram => stats.nodes = histogram(elements),
+            LevelEstimator::Sample => {
+                let (nodes, margins) = sample(elements, args.level_sample_size, args.seed);
+                stats.nodes = nodes;
+                stats.margins = margins;
+            }
+        }
+        stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    pub fn max(&self) -> DataType {
+        self.elements.last().cloned().unwrap_or(DataType::from(0u64))
+    }
+
+    ///(estimated) number of distinct values, if the lowest level bits are cut off
+    pub fn nodes_in_level(&self, level: usize) -> f64 {
+        match self.estimator {
+            LevelEstimator::Scan => scan(self.elements, level),
+            _ => self.nodes[level],
+        }
+    }
+
+    ///half width of the 95% confidence interval of nodes_in_level
+    pub fn margin(&self, level: usize) -> f64 {
+        self.margins[level]
+    }
+
+    ///prints the selected levels and their estimated number of nodes and load
+    ///start_level and top_level are given as number of cut off bits
+    pub fn report(&self, start_level: usize, top_level: usize, bit_length: usize, log: &Log) {
+        let load = |level: usize| self.nodes_in_level(level) / 2f64.powf((bit_length - level) as f64);
+        log.print_result(format!("level_estimator={:?}\tstart_level={}\tstart_level_nodes={}\tstart_level_margin={}\tstart_level_load={}\ttop_level={}\ttop_level_nodes={}\ttop_level_margin={}\ttop_level_load={}",
+                                 self.estimator, start_level, self.nodes_in_level(start_level), self.margin(start_level), load(start_level),
+                                 top_level, self.nodes_in_level(top_level), self.margin(top_level), load(top_level)));
+    }
+}
+
+///count how many nodes are in one level
+fn scan(elements: &[DataType], level: usize) -> f64 {
+    let mut last_val = u64::from(elements[0]) >> level;
+    let mut count = 1.;
+    for value in elements {
+        let new_val = u64::from(*value) >> level;
+        if new_val != last_val {
+            count += 1.;
+            last_val = new_val;
+        }
+    }
+    count
+}
+
+///number of levels, in which two neighbouring elements have different prefixes
+///(their prefixes differ as long as the highest differing bit isn't cut off)
+#[inline]
+fn split_levels(lower: DataType, upper: DataType) -> usize {
+    64 - (u64::from(lower) ^ u64::from(upper)).leading_zeros() as usize
+}
+
+///nodes of all levels in one pass
+fn histogram(elements: &[DataType]) -> Vec<f64> {
+    // splits[i] = number of neighbours, that are split in the levels 0 to i - 1
+    let mut splits = vec![0usize; LEVELS + 1];
+    for pair in elements.windows(2) {
+        splits[split_levels(pair[0], pair[1])] += 1;
+    }
+    // a neighbour pair adds one node to every level below its split height
+    let mut nodes = vec![0.; LEVELS];
+    let mut count = 1;
+    for level in (0..LEVELS).rev() {
+        count += splits[level + 1];
+        nodes[level] = count as f64;
+    }
+    nodes
+}
+
+///nodes of all levels estimated from sample_size random neighbour pairs
+///returns (nodes, margins)
+fn sample(elements: &[DataType], sample_size: usize, seed: u64) -> (Vec<f64>, Vec<f64>) {
+    let pairs = elements.len() - 1;
+    if pairs <= sample_size {
+        return (histogram(elements), vec![0.; LEVELS]);
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut splits = vec![0usize; LEVELS + 1];
+    for _ in 0..sample_size {
+        let pos = rng.gen_range(0, pairs);
+        splits[split_levels(elements[pos], elements[pos + 1])] += 1;
+    }
+    let mut nodes = vec![0.; LEVELS];
+    let mut margins = vec![0.; LEVELS];
+    let mut count = 0;
+    for level in (0..LEVELS).rev() {
+        count += splits[level + 1];
+        //ratio of neighbour pairs, that are split in this level
+        let ratio = count as f64 / sample_size as f64;
+        nodes[level] = 1. + ratio * pairs as f64;
+        margins[level] = Z_95 * (ratio * (1. - ratio) / sample_size as f64).sqrt() * pairs as f64;
+    }
+    (nodes, margins)
+}
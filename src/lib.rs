@@ -12,6 +12,7 @@ extern crate stats_alloc;
 /// see main.rs for "cargo run"
 
 pub mod yft64;
+pub mod yft40_generic_hash;
 pub mod yft40_rust_hash;
 pub mod yft40sn_fx_hash;
 pub mod yft40bn_fx_hash;
@@ -36,6 +37,18 @@ pub mod yft40_no_level_bin_suc;
 pub mod yft40_no_level_bin;
 pub mod yft40_fnv_hash;
 pub mod yft40so_fnv_small_groups;
+pub mod yft40so_generic_binsearch;
+pub mod yft40so_hash_brown_binsearch;
+pub mod yft40so_packed_binsearch;
+pub mod yft40so_mphf_binsearch;
+pub mod yft40so_fnv_bloom_binsearch;
+pub mod yft40so_fnv_eytzinger_binsearch;
+pub mod yft40so_fnv_block8_binsearch;
+pub mod yft40so_fnv_block16_binsearch;
+pub mod yft40so_fnv_elias_fano_binsearch;
+pub mod yft40so_fnv_interpolation_binsearch;
+pub mod yft40so_fnv_linear_model_binsearch;
+pub mod yft40so_fnv_trained_binsearch;
 pub mod yft40sn_fnv;
 pub mod yft40sn_bin_fnv;
 pub mod yft40_split;
@@ -43,6 +56,23 @@ pub mod yft40_split_small;
 pub mod yft40_split_small_leaf_search;
 pub mod yft64_split_small_32;
 pub mod predecessor_set;
+pub mod veb;
+pub mod level_keys;
+pub mod xft40_fnv;
+pub mod zft64_fnv;
+pub mod btree40;
+pub mod level_map;
+pub mod level_search;
+pub mod heap_size;
+pub mod leaf_layout;
+pub mod simd_search;
+pub mod packed_suffixes;
+pub mod elias_fano;
+pub mod packed_map;
+pub mod mphf_map;
+pub mod bloom_filter;
+pub mod level_stats;
+pub mod autotune;
 pub mod nmbrsrc;
 pub mod log;
 pub mod args;
@@ -51,20 +81,41 @@ pub mod vec_search;
 #[cfg(test)]
 mod tests {
     use super::*;
-    use args::{Args, ValueSrc};
+    use args::{Args, ValueSrc, StoreFormat, Compression, LevelEstimator};
+    use predecessor_set::PredecessorSet;
     use uint::u40;
+    use level_map::LookupStats;
 
-    #[test]
-    fn test() {
-        let args = Args {
+    fn test_args() -> Args {
+        Args {
             values: ValueSrc::Uniform { length: 0 },
             min_start_level: 10,
             search_stats: false,
             element_length_test: false,
+            sizes: Vec::new(),
+            leaf_levels: Vec::new(),
+            size_factor: 0.5,
+            random_subsample: false,
+            seed: 0,
             fixed_leaf_level: Some(8),
             fixed_top_level: Some(32),
             implementation: 1,
             bin_middle: 30,
+            level_load_factor: 75,
+            fingerprint_bits: 8,
+            filter_bits: 10,
+            threads: 2,
+            level_estimator: LevelEstimator::Histogram,
+            level_sample_size: 65536,
+            level_training_file: None,
+            level_training_queries: 100000,
+            autotune: false,
+            autotune_implementations: Vec::new(),
+            autotune_start_levels: Vec::new(),
+            autotune_top_levels: Vec::new(),
+            autotune_sample: 1048576,
+            autotune_queries: 100000,
+            memory_budget: None,
             memory: false,
             run_name: None,
             add: None,
@@ -72,16 +123,52 @@ mod tests {
             queries: None,
             result: false,
             store: None,
+            store_format: StoreFormat::Load,
+            store_compression: Compression::None,
+            stream: false,
             time: false,
             u40: true,
             min_load_factor_difference: 99,
             min_start_level_load_factor: 1,
             max_last_level_load_factor: 99,
             max_lss_level: 8,
-        };
-        let mut log = log::Log::new(String::from("Test"));
+        }
+    }
 
+    /// deletes every third of the first 3000 values and checks the remaining ones
+    fn check_delete<T: PredecessorSet<u40>>(set: &mut T, values: &Vec<u40>, queries: &Vec<u40>) -> Vec<u40> {
+        let mut remaining = values.clone();
+        for i in (0..1000).rev() {
+            set.delete(remaining.remove(i * 3));
+        }
+        assert_eq!(set.minimum(), remaining.first().cloned());
+        assert_eq!(set.maximum(), remaining.last().cloned());
+        for query in queries.iter() {
+            assert_eq!(set.predecessor(*query), vec_search::rust_bin_search_pred(&remaining, *query));
+            let successor = match remaining.binary_search(query) {
+                Ok(pos) => remaining.get(pos + 1).cloned(),
+                Err(pos) => remaining.get(pos).cloned(),
+            };
+            assert_eq!(set.successor(*query), successor);
+        }
+        remaining
+    }
 
+    /// values and queries of the predecessor tests
+    struct Fixture {
+        values1: Vec<u40>,
+        values2: Vec<u40>,
+        rnd_values: Vec<u40>,
+        rnd_queries: Vec<u40>,
+        // fixed queries followed by rnd_queries
+        queries: Vec<u40>,
+        // predecessors of the queries in values1, values2 and rnd_values
+        results_1: Vec<Option<u40>>,
+        results_2: Vec<Option<u40>>,
+        results_r: Vec<Option<u40>>,
+    }
+
+    fn fixture() -> Fixture {
         let values1 = vec![
             0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39,
             40, 701, 702, 703, 704, 705, 706, 707, 708, 709, 710, 711, 712, 713, 714, 715, 716, 717, 718, 719, 720, 721, 722, 723, 724, 725, 726, 727, 728, 729, 730, 731, 732, 733, 734, 735, 736, 737, 738, 739,
@@ -96,7 +183,7 @@ mod tests {
             10804527104, 10804527204, 10804527304, 10804527404, 10804527504, 10804527604, 10804527704, 10804527804, 10804527904, 10804528004, 10804528104, 10804528204, 10804528304, 10804528404, 10804528504, 10804528604, 10804528704, 10804528804, 10804528904, 10804529004, 10804529104, 10804529204, 10804529304, 10804529404, 10804529504, 10804529604, 10804529704, 10804529804, 10804529904, 10804530004, 10804530104, 10804530204, 10804530304, 10804530404, 10804530504, 10804530604, 10804530704, 10804530804, 100804530904, 110804531004,
             1099511627774, 1099511627775]
             .iter().map(|v: &u64| u40::from(*v)).collect();
-        let mut rnd_values = nmbrsrc::get_uniform_dist(32768);
+        let rnd_values = nmbrsrc::get_uniform_dist(32768);
         let rnd_queries: Vec<u40> = nmbrsrc::get_uniform_dist(32768);
         let mut queries: Vec<u40> = vec![
             0, 1, 39,
@@ -117,6 +204,28 @@ mod tests {
             results_2.push(vec_search::rust_bin_search_pred(&values2, *query));
             results_r.push(vec_search::rust_bin_search_pred(&rnd_values, *query));
         }
+        Fixture { values1, values2, rnd_values, rnd_queries, queries, results_1, results_2, results_r }
+    }
+
+    /// builds sets of values1, values2 and rnd_values with new and checks the predecessors of all queries
+    /// returns the set of rnd_values for further checks
+    fn check_static<T: PredecessorSet<u40>, F: FnMut(Vec<u40>) -> T>(fixture: &Fixture, mut new: F) -> T {
+        let set1 = new(fixture.values1.clone());
+        let set2 = new(fixture.values2.clone());
+        let set_r = new(fixture.rnd_values.clone());
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            assert_eq!(set1.predecessor(*query), fixture.results_1[pos]);
+            assert_eq!(set2.predecessor(*query), fixture.results_2[pos]);
+            assert_eq!(set_r.predecessor(*query), fixture.results_r[pos]);
+        }
+        set_r
+    }
+
+    #[test]
+    fn test() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let Fixture { values1, values2, mut rnd_values, queries, results_1, results_2, results_r, .. } = fixture();
 
         {
             let yft1 = yft40sn_fx_hash::YFT::new(values1.clone(), &args, &mut log);
@@ -154,7 +263,6 @@ mod tests {
             }
         }
 
-
         {
             let yft1 = yft40so_fnv_binsearch::YFT::new(values1.clone(), &args, &mut log);
             let yft2 = yft40so_fnv_binsearch::YFT::new(values2.clone(), &args, &mut log);
@@ -288,4 +396,426 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_split_small_from_sorted_iter() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        //yft40_split_small requires unique elements, the predecessors stay the same
+        let mut fixture = fixture();
+        fixture.rnd_values.dedup();
+        check_static(&fixture, |values| yft40_split_small::YFT::from_sorted_iter(values.into_iter(), &args, &mut log));
+    }
+
+    #[test]
+    fn test_generic_level_maps() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        check_static(&fixture, |values| yft40_boomphf_hash::YFT::new(values, &args, &mut log));
+        check_static(&fixture, |values| yft40so_hash_brown_binsearch::YFT::new(values, &args, &mut log));
+    }
+
+    #[test]
+    fn test_packed_level_map() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        check_static(&fixture, |values| yft40so_packed_binsearch::YFT::new(values, &args, &mut log));
+
+        //leaf level 0 stores 2^40 - 1 of values2 as key, which marks empty slots
+        let level_args = Args { fixed_leaf_level: Some(0), fixed_top_level: Some(20), ..args.clone() };
+        let yft2 = yft40so_packed_binsearch::YFT::new(fixture.values2.clone(), &level_args, &mut log);
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            assert_eq!(yft2.predecessor(*query), fixture.results_2[pos]);
+        }
+    }
+
+    #[test]
+    fn test_mphf_level_map() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        let yftr = check_static(&fixture, |values| yft40so_mphf_binsearch::YFT::new(values, &args, &mut log));
+
+        //a hash table lookup is one probe, the mphf compares the fingerprint and for matching ones the key
+        let yft_table = yft40so_fnv_binsearch::YFT::new(fixture.rnd_values.clone(), &args, &mut log);
+        let mut mphf_stats = LookupStats::default();
+        let mut table_stats = LookupStats::default();
+        let mut steps = 0;
+        for query in fixture.queries.iter() {
+            yftr.predecessor_with_stats(*query, &mut mphf_stats);
+            steps += yft_table.predecessor_with_stats(*query, &mut table_stats).2 as usize;
+        }
+        assert_eq!(table_stats.probes, steps);
+        assert!(mphf_stats.probes > 0 && mphf_stats.probes <= 2 * steps);
+    }
+
+    #[test]
+    fn test_bloom_level_map() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        let yftr = check_static(&fixture, |values| yft40so_fnv_bloom_binsearch::YFT::new(values, &args, &mut log));
+
+        let mut lookup_stats = LookupStats::default();
+        let mut hash_misses = 0;
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            let (result, _, _, misses) = yftr.predecessor_with_stats(*query, &mut lookup_stats);
+            assert_eq!(result, fixture.results_r[pos]);
+            hash_misses += misses as usize;
+        }
+        //only lookups of predecessor_with_stats are counted, every avoided lookup is a miss
+        assert!(lookup_stats.avoided_lookups > 0);
+        assert!(lookup_stats.avoided_lookups <= hash_misses);
+    }
+
+    #[test]
+    fn test_parallel_construction() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        check_static(&fixture(), |values| yft40so_fnv_binsearch::YFT::new_parallel(values, &args, &mut log));
+    }
+
+    #[test]
+    fn test_level_estimators() {
+        let values = nmbrsrc::get_uniform_dist(32768);
+        let pairs = (values.len() - 1) as f64;
+        let scan = level_stats::LevelStats::new(&values, &Args { level_estimator: LevelEstimator::Scan, ..test_args() });
+        let histogram = level_stats::LevelStats::new(&values, &Args { level_estimator: LevelEstimator::Histogram, ..test_args() });
+        let sample = level_stats::LevelStats::new(&values, &Args { level_estimator: LevelEstimator::Sample, level_sample_size: 4096, ..test_args() });
+        for level in 0..41 {
+            let nodes = scan.nodes_in_level(level);
+            assert_eq!(histogram.nodes_in_level(level), nodes);
+            //the margin is a normal approximation, which only holds, if enough pairs are split and not split
+            let ratio = (nodes - 1.) / pairs;
+            if ratio > 0.05 && ratio < 0.95 {
+                let estimate = sample.nodes_in_level(level);
+                assert!((estimate - nodes).abs() <= 3. * sample.margin(level), "level {}: {} sampled nodes, {} nodes, margin {}", level, estimate, nodes, sample.margin(level));
+            }
+        }
+    }
+
+    #[test]
+    fn test_memory_budget() {
+        let args = Args { memory_budget: Some(1 << 30), ..test_args() };
+        let mut log = log::Log::new(String::from("Test"));
+        check_static(&fixture(), |values| yft40so_fnv_binsearch::YFT::new(values, &args, &mut log));
+    }
+
+    #[test]
+    fn test_memory_budget_raises_leaf_level() {
+        //a budget below the size of the calculated levels raises the leaf level
+        //(the calculated leaf level is the highest one, where no two elements share a node)
+        let args = Args { fixed_leaf_level: None, min_start_level_load_factor: 100, ..test_args() };
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        let start_level = yft40so_fnv_binsearch::YFT::new(fixture.rnd_values.clone(), &args, &mut log).start_level();
+        let stats = level_stats::LevelStats::new(&fixture.rnd_values, &args);
+        let budget = yft40so_fnv_binsearch::YFT::estimate_size(&stats, start_level + 2, 32, &level_map::LevelMapConfig::from_args(&args));
+        let args = Args { memory_budget: Some(budget), ..args };
+        let yftr = yft40so_fnv_binsearch::YFT::new(fixture.rnd_values.clone(), &args, &mut log);
+        assert_eq!(yftr.start_level(), start_level + 2);
+
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            assert_eq!(yftr.predecessor(*query), fixture.results_r[pos]);
+        }
+    }
+
+    #[test]
+    fn test_element_layouts() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        check_static(&fixture, |values| yft40so_fnv_eytzinger_binsearch::YFT::new(values, &args, &mut log));
+        check_static(&fixture, |values| yft40so_fnv_block8_binsearch::YFT::new(values, &args, &mut log));
+        check_static(&fixture, |values| yft40so_fnv_block16_binsearch::YFT::new(values, &args, &mut log));
+    }
+
+    #[test]
+    fn test_elias_fano_elements() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        let yftr = check_static(&fixture, |values| yft40so_fnv_elias_fano_binsearch::YFT::new(values, &args, &mut log));
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            //rank counts the smaller elements, so the element with rank - 1 is the predecessor
+            assert_eq!(yftr.rank(*query).checked_sub(1).and_then(|rank| yftr.select(rank)), fixture.results_r[pos]);
+        }
+    }
+
+    #[test]
+    fn test_split_small_suffix_widths() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        //yft40_split_small requires unique elements, the predecessors stay the same
+        let mut fixture = fixture();
+        fixture.rnd_values.dedup();
+
+        //suffix widths above 16 bit and the simd widths 16 and 32
+        for leaf_level in &[16, 20, 32] {
+            let level_args = Args { fixed_leaf_level: Some(*leaf_level), fixed_top_level: Some(36), ..args.clone() };
+            let mut yftr = check_static(&fixture, |values| yft40_split_small::YFT::new(values, &level_args, &mut log));
+
+            let mut level_values = fixture.rnd_values.clone();
+            for i in nmbrsrc::get_uniform_dist(4096) {
+                yftr.add(i);
+                level_values.push(i);
+            }
+            level_values.sort();
+            level_values.dedup();
+            for i in (0..1000).rev() {
+                yftr.remove(level_values.remove(i * 2));
+            }
+            for query in fixture.queries.iter() {
+                assert_eq!(yftr.predecessor(*query), vec_search::rust_bin_search_pred(&level_values, *query));
+            }
+        }
+    }
+
+    #[test]
+    fn test_packed_suffixes() {
+        //xorshift, so a failing width can be reproduced
+        let mut seed = 0x2545_F491_4F6C_DD1Du64;
+        let mut random = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        //widths, that are shifted word wise, and the copied widths 8, 16 and 32
+        for width in &[1, 5, 8, 12, 16, 20, 24, 31, 32, 39] {
+            let mask = (1u64 << width) - 1;
+            let first = random() & mask;
+            let mut suffixes = packed_suffixes::PackedSuffixes::from_suffix(*width, first);
+            let mut expected = vec![first];
+            for _ in 0..3000 {
+                let suffix = random() & mask;
+                match expected.binary_search(&suffix) {
+                    Ok(pos) => {
+                        assert_eq!(suffixes.binary_search(suffix), Ok(pos));
+                        if random() % 3 == 0 {
+                            expected.remove(pos);
+                            suffixes.remove(pos);
+                        }
+                    }
+                    Err(pos) => {
+                        assert_eq!(suffixes.binary_search(suffix), Err(pos));
+                        expected.insert(pos, suffix);
+                        suffixes.insert(pos, suffix);
+                    }
+                }
+            }
+            assert_eq!(suffixes.len(), expected.len());
+            for (pos, suffix) in expected.iter().enumerate() {
+                assert_eq!(suffixes.get(pos), *suffix, "width {}", width);
+            }
+        }
+    }
+
+    #[test]
+    fn test_leaf_models() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        check_static(&fixture, |values| yft40so_fnv_interpolation_binsearch::YFT::new(values, &args, &mut log));
+        check_static(&fixture, |values| yft40so_fnv_linear_model_binsearch::YFT::new(values, &args, &mut log));
+    }
+
+    #[test]
+    fn test_vec_search() {
+        let fixture = fixture();
+        let hint = std::cell::Cell::new(0);
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            assert_eq!(vec_search::interpolation_search_pred(&fixture.values1, *query), fixture.results_1[pos]);
+            assert_eq!(vec_search::interpolation_sequential_search_pred(&fixture.values2, *query), fixture.results_2[pos]);
+            assert_eq!(vec_search::exponential_search_pred(&fixture.rnd_values, *query, &hint), fixture.results_r[pos]);
+            assert_eq!(vec_search::branchless_bin_search_pred(&fixture.rnd_values, *query), fixture.results_r[pos]);
+        }
+    }
+
+    #[test]
+    fn test_veb() {
+        let fixture = fixture();
+        let mut veb = veb::VEB::new(40);
+        for value in fixture.rnd_values.iter() {
+            PredecessorSet::<u40>::insert(&mut veb, *value);
+        }
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            assert_eq!(PredecessorSet::<u40>::predecessor(&veb, *query), fixture.results_r[pos]);
+        }
+        check_delete(&mut veb, &fixture.rnd_values, &fixture.queries);
+    }
+
+    #[test]
+    fn test_xft() {
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        let mut xft = xft40_fnv::XFT::from_elements(fixture.rnd_values.clone(), &mut log);
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            assert_eq!(xft.predecessor(*query), fixture.results_r[pos]);
+        }
+        check_delete(&mut xft, &fixture.rnd_values, &fixture.queries);
+    }
+
+    #[test]
+    fn test_zft() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        let to_u64 = |values: &Vec<u40>| values.iter().map(|v| u64::from(*v)).collect::<Vec<u64>>();
+        let zft1 = zft64_fnv::ZFT::new(to_u64(&fixture.values1), 40, &args, &mut log);
+        let zft2 = zft64_fnv::ZFT::new(to_u64(&fixture.values2), 64, &args, &mut log);
+        let zftr = zft64_fnv::ZFT::new(to_u64(&fixture.rnd_values), 40, &args, &mut log);
+
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            let query = u64::from(*query);
+            assert_eq!(zft1.predecessor_u64(query).map(|v| u40::from(v)), fixture.results_1[pos]);
+            assert_eq!(zft2.predecessor_u64(query).map(|v| u40::from(v)), fixture.results_2[pos]);
+            assert_eq!(zftr.predecessor_with_stats_u64(query).0.map(|v| u40::from(v)), fixture.results_r[pos]);
+        }
+    }
+
+    #[test]
+    fn test_btree40() {
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        let mut tree = btree40::BTree::from_sorted(fixture.rnd_values.clone(), &mut log);
+        let mut inserted = btree40::BTree::new();
+        for value in fixture.values1.iter() {
+            inserted.insert(*value);
+        }
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            assert_eq!(tree.predecessor(*query), fixture.results_r[pos]);
+            assert_eq!(inserted.predecessor(*query), fixture.results_1[pos]);
+        }
+        let remaining = check_delete(&mut tree, &fixture.rnd_values, &fixture.queries);
+        assert_eq!(tree.len(), remaining.len());
+    }
+
+    #[test]
+    fn test_trained_level_search() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let fixture = fixture();
+        //skewed training: only queries of the small values, the other queries have to work too
+        for training_queries in &[&fixture.queries[..11], &fixture.queries[..]] {
+            check_static(&fixture, |values| {
+                let mut yft = yft40so_fnv_trained_binsearch::YFT::new(values, &args, &mut log);
+                yft.train_level_search(training_queries);
+                yft
+            });
+        }
+
+        let mut yftr = yft40so_fnv_trained_binsearch::YFT::new(fixture.rnd_values.clone(), &args, &mut log);
+        yftr.train_level_search(&fixture.rnd_queries);
+        for (pos, query) in fixture.queries.iter().enumerate() {
+            assert_eq!(yftr.predecessor(*query), fixture.results_r[pos]);
+            assert_eq!(yftr.predecessor_with_stats(*query, &mut LookupStats::default()).0, fixture.results_r[pos]);
+        }
+        //the table of the trained search is part of the heap size
+        assert!(heap_size::TrieMemory::memory_breakdown(&yftr).level_search > 0);
+    }
+
+    #[test]
+    fn test_trained_level_search_probes() {
+        let args = test_args();
+        let mut log = log::Log::new(String::from("Test"));
+        let rnd_values = fixture().rnd_values;
+        //skewed queries (successors of the elements) mostly end in the leaf level
+        //trained with the even elements, measured with the disjoint odd elements
+        let skewed_queries: Vec<u40> = rnd_values.iter().filter(|v| u64::from(**v) < (1 << 40) - 1).map(|v| u40::from(u64::from(*v) + 1)).collect();
+        let training_queries: Vec<u40> = skewed_queries.iter().step_by(2).cloned().collect();
+        let test_queries: Vec<u40> = skewed_queries.iter().skip(1).step_by(2).cloned().collect();
+        let mut yft_trained = yft40so_fnv_trained_binsearch::YFT::new(rnd_values.clone(), &args, &mut log);
+        yft_trained.train_level_search(&training_queries);
+        let yft_midpoint = yft40so_fnv_binsearch::YFT::new(rnd_values.clone(), &args, &mut log);
+        let mut trained_probes = 0;
+        let mut midpoint_probes = 0;
+        for query in &test_queries {
+            let (result, _, steps, _) = yft_trained.predecessor_with_stats(*query, &mut LookupStats::default());
+            assert_eq!(result, yft_midpoint.predecessor(*query));
+            trained_probes += steps;
+            midpoint_probes += yft_midpoint.predecessor_with_stats(*query, &mut LookupStats::default()).2;
+        }
+        assert!(trained_probes <= midpoint_probes, "trained search needs {} probes, midpoint search {}", trained_probes, midpoint_probes);
+    }
+
+    #[test]
+    fn test_bloom_filter_equal_low_bits() {
+        //sparse ids: all keys end with the same 16 bits
+        let key = |i: u64| u40::from(i << 16 | 0x1234);
+        let keys: Vec<u40> = (0..100000).map(|i| key(2 * i)).collect();
+        let filter = bloom_filter::BlockedBloomFilter::new(&keys, bloom_filter::DEFAULT_BITS_PER_KEY);
+        assert!(keys.iter().all(|k| filter.may_contain(*k)));
+        let false_positives = (0..100000).filter(|i| filter.may_contain(key(2 * i + 1))).count();
+        //about 1% for 10 bits per key
+        assert!(false_positives < 3000, "{} false positives of 100000", false_positives);
+    }
+
+    #[test]
+    fn test_store_formats() {
+        let values = nmbrsrc::get_uniform_dist(1000);
+        let values_usize: Vec<usize> = values.iter().map(|v| u64::from(*v) as usize).collect();
+        let path = std::env::temp_dir().join("yft_test_store_formats");
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+        nmbrsrc::save(&values_usize, path, Compression::None).unwrap();
+        assert_eq!(nmbrsrc::load(path).unwrap(), values_usize);
+        nmbrsrc::save_u64_serialized(&values_usize, path, Compression::None).unwrap();
+        assert_eq!(nmbrsrc::load_u64_serialized(path).unwrap(), values_usize);
+        nmbrsrc::save_u40_serialized(&values, path, Compression::None).unwrap();
+        assert_eq!(nmbrsrc::load_u40_serialized(path).unwrap(), values);
+        nmbrsrc::save_u40_fit(&values, path, Compression::None).unwrap();
+        assert_eq!(nmbrsrc::load_u40_fit(path).unwrap(), values);
+        nmbrsrc::save_u40_tim(&values, path, Compression::None).unwrap();
+        assert_eq!(nmbrsrc::load_u40_tim(path).unwrap(), values);
+        nmbrsrc::save_u64_tim(&values_usize, path, Compression::None).unwrap();
+        assert_eq!(nmbrsrc::load_u64_tim(path).unwrap(), values_usize);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_declared_number_of_values() {
+        use std::io::Write;
+        let values = nmbrsrc::get_uniform_dist(1000);
+        for compression in &[Compression::None, Compression::Gzip, Compression::Zstd] {
+            let path = std::env::temp_dir().join(format!("yft_test_declared_number_{:?}", compression));
+            let path = path.to_str().unwrap();
+            //header with one value more or less than the file contains
+            for declared in &[values.len() + 1, values.len() - 1] {
+                let mut output = nmbrsrc::Output::create(path, *compression).unwrap();
+                output.write_all(&declared.to_le_bytes()).unwrap();
+                for value in &values {
+                    output.write_all(&u64::from(*value).to_le_bytes()[0..5]).unwrap();
+                }
+                output.finish().unwrap();
+                let error = nmbrsrc::load_u40_tim(path).unwrap_err();
+                let expected = if *declared > values.len() { std::io::ErrorKind::UnexpectedEof } else { std::io::ErrorKind::InvalidData };
+                assert_eq!(error.kind(), expected);
+            }
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_compression_by_magic_bytes() {
+        let values = nmbrsrc::get_uniform_dist(1000);
+        for compression in &[Compression::None, Compression::Gzip, Compression::Zstd] {
+            //no file extension, the compression has to be detected from the content
+            let path = std::env::temp_dir().join(format!("yft_test_magic_bytes_{:?}", compression));
+            let path = path.to_str().unwrap();
+            nmbrsrc::save_u40_tim(&values, path, *compression).unwrap();
+            assert_eq!(nmbrsrc::load_u40_tim(path).unwrap(), values);
+            std::fs::remove_file(path).unwrap();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Memory budget of 1 bytes is too small")]
+    fn test_impossible_memory_budget() {
+        let args = Args { memory_budget: Some(1), ..test_args() };
+        let values = nmbrsrc::get_uniform_dist(32768);
+        yft40so_fnv_binsearch::YFT::new(values, &args, &mut log::Log::new(String::from("Test")));
+    }
+}
@@ -76,4 +76,21 @@ impl<'a> Log<'a> {
     pub fn print_result(&self, values: String) {
         println!("RESULT\trun={}\tnumber={}\t{}", self.run_name, self.run_number, values);
     }
-}
\ No newline at end of file
+}
+
+/// measures the heap memory allocated since its creation, independent of the memory logging option
+pub struct MemProbe<'a> {
+    reg: Region<'a, System>,
+}
+
+impl<'a> MemProbe<'a> {
+    pub fn new() -> MemProbe<'a> {
+        MemProbe { reg: Region::new(&GLOBAL) }
+    }
+
+    /// bytes allocated and not freed since creation (0 if more was freed)
+    pub fn bytes(&self) -> usize {
+        let stats = self.reg.change();
+        stats.bytes_allocated.saturating_sub(stats.bytes_deallocated)
+    }
+}
@@ -18,11 +18,20 @@ use structopt::StructOpt;
 use uint::u40;
 use args::Args;
 use args::ValueSrc;
+use args::StoreFormat;
+use args::Compression;
 use std::collections::BTreeSet;
 use self::fnv::FnvHashSet;
 use std::iter::FromIterator;
+use std::cell::{Cell, RefCell};
+use predecessor_set::PredecessorSet;
+use level_map::LookupStats;
+use vec_search::SearchKey;
+use std::rc::Rc;
+use std::path::PathBuf;
 
 pub mod yft64;
+pub mod yft40_generic_hash;
 pub mod yft40_rust_hash;
 pub mod yft40sn_fx_hash;
 pub mod yft40bn_fx_hash;
@@ -47,6 +56,18 @@ pub mod yft40_no_level_bin_suc;
 pub mod yft40_no_level_bin;
 pub mod yft40_fnv_hash;
 pub mod yft40so_fnv_small_groups;
+pub mod yft40so_generic_binsearch;
+pub mod yft40so_hash_brown_binsearch;
+pub mod yft40so_packed_binsearch;
+pub mod yft40so_mphf_binsearch;
+pub mod yft40so_fnv_bloom_binsearch;
+pub mod yft40so_fnv_eytzinger_binsearch;
+pub mod yft40so_fnv_block8_binsearch;
+pub mod yft40so_fnv_block16_binsearch;
+pub mod yft40so_fnv_elias_fano_binsearch;
+pub mod yft40so_fnv_interpolation_binsearch;
+pub mod yft40so_fnv_linear_model_binsearch;
+pub mod yft40so_fnv_trained_binsearch;
 pub mod yft40sn_fnv;
 pub mod yft40sn_bin_fnv;
 pub mod yft40_split;
@@ -54,6 +75,23 @@ pub mod yft40_split_small;
 pub mod yft40_split_small_leaf_search;
 pub mod yft64_split_small_32;
 pub mod predecessor_set;
+pub mod veb;
+pub mod level_keys;
+pub mod xft40_fnv;
+pub mod zft64_fnv;
+pub mod btree40;
+pub mod level_map;
+pub mod level_search;
+pub mod heap_size;
+pub mod leaf_layout;
+pub mod simd_search;
+pub mod packed_suffixes;
+pub mod elias_fano;
+pub mod packed_map;
+pub mod mphf_map;
+pub mod bloom_filter;
+pub mod level_stats;
+pub mod autotune;
 pub mod nmbrsrc;
 pub mod log;
 pub mod args;
@@ -68,6 +106,13 @@ fn main() {
             }
         }
     }
+    //autotune applies the budget to its candidates, the tries fit into it only with the levels of yft40so_generic_binsearch
+    if args.memory_budget.is_some() && !args.autotune {
+        match args.implementation {
+            21 | 23..=26 | 28 | 31..=41 => {}
+            h => panic!("--memory-budget works only with h = 21, 23 - 26, 28 and 31 - 41, not with -h {}", h),
+        }
+    }
     println!("{:?}", args);
 
     let mut log =
@@ -89,6 +134,12 @@ fn main() {
     };
 
 
+    if args.stream {
+        run_stream(&args, &mut log);
+        log.log_mem("end");
+        return;
+    }
+
     //create yft input (u64, u40)
     let values: (Vec<usize>, Vec<u40>) =
         match &args.values {
@@ -116,6 +167,15 @@ fn main() {
             ValueSrc::PowerLaw { length, n } => {
                 (nmbrsrc::get_power_law_dist(*length, *n), Vec::new())
             }
+            ValueSrc::Ipv4 { length, blocks, min_prefix, max_prefix } => {
+                (nmbrsrc::get_ipv4_dist(*length, *blocks, *min_prefix, *max_prefix), Vec::new())
+            }
+            ValueSrc::Timestamps { length, days, peak_factor } => {
+                (nmbrsrc::get_timestamp_dist(*length, *days, *peak_factor), Vec::new())
+            }
+            ValueSrc::SparseIds { length, shards, shard_bits, mean_gap } => {
+                (nmbrsrc::get_sparse_id_dist(*length, *shards, *shard_bits, *mean_gap), Vec::new())
+            }
             ValueSrc::Load { path } => {
                 (nmbrsrc::load(path.to_str().unwrap()).unwrap(), Vec::new())
             }
@@ -138,30 +198,39 @@ fn main() {
 
     //save input if option is set
     if let Some(ref file) = args.store {
-        //generated values are alway u64
-        if let Err(e) = nmbrsrc::save(&values.0, file.to_str().unwrap()) {
+        if let Err(e) = store_values(&values, file.to_str().unwrap(), args.store_format, args.store_compression) {
             dbg!(e);
         }
     }
 
-    if !args.element_length_test {
+    if args.autotune {
+        let file = args.queries.as_ref().expect("autotune requires a query file (-q)");
+        let queries: Vec<u40> = load_queries(file, &args).iter().map(|v| u40::from(*v)).collect();
+        log.log_mem("values loaded").log_time("values loaded");
+        autotune::run(get_u40_values(values), queries, &args, &mut log);
+    } else if args.leaf_levels.len() > 0 {
+        for level in &args.leaf_levels {
+            log.reset_memlog();
+            let mut level_args = args.clone();
+            level_args.fixed_leaf_level = Some(*level);
+            log.print_result(format!("info=leaf level test\tleaf_level={}", level));
+
+            run_yft(&level_args, &mut log, values.clone());
+            log.inc_run_number();
+        }
+    } else if !args.element_length_test {
         run_yft(&args, &mut log, values);
     } else {
-        for i in 0..40 { //for element length test, else ignored
+        let seed = if args.random_subsample { Some(args.seed) } else { None };
+        for size in element_length_test_sizes(values.0.len().max(values.1.len()), &args) {
             log.reset_memlog();
-            let iteration_values: (Vec<usize>, Vec<u40>);
-            //decrease number of elements if option is set
-            if values.0.len() == 0 {
-                iteration_values = (Vec::new(), values.1.iter().step_by(2usize.pow(i)).map(|v| v.clone()).collect());
-                if iteration_values.1.len() < 2 {
-                    break;
-                }
+            //decrease number of elements
+            let iteration_values: (Vec<usize>, Vec<u40>) = if values.0.len() == 0 {
+                (Vec::new(), nmbrsrc::subsample(&values.1, size, seed))
             } else {
-                iteration_values = (values.0.iter().step_by(2usize.pow(i)).map(|v| v.clone()).collect(), Vec::new());
-                if iteration_values.0.len() < 2 {
-                    break;
-                }
-            }
+                (nmbrsrc::subsample(&values.0, size, seed), Vec::new())
+            };
+            log.print_result(format!("info=element length test\telements={}", size));
 
             run_yft(&args, &mut log, iteration_values);
             //log is not used between begin of for loop and here -> no problems
@@ -172,6 +241,29 @@ fn main() {
     log.log_mem("end");
 }
 
+/// number of elements for each run of the element length test
+/// either the sizes given with --sizes or sizes reduced by --size-factor, until less than 2 elements are left
+fn element_length_test_sizes(len: usize, args: &Args) -> Vec<usize> {
+    if args.sizes.len() > 0 {
+        if let Some(size) = args.sizes.iter().find(|s| **s > len) {
+            panic!("--sizes contains {}, but the input has only {} elements", size, len);
+        }
+        return args.sizes.clone();
+    }
+    if !(args.size_factor > 0. && args.size_factor < 1.) {
+        panic!("--size-factor has to be between 0 and 1");
+    }
+    let mut sizes = Vec::new();
+    let mut size = len;
+    while size >= 2 {
+        sizes.push(size);
+        let next_size = (size as f64 * args.size_factor) as usize;
+        //ensure every run has less elements
+        size = if next_size < size { next_size } else { size - 1 };
+    }
+    sizes
+}
+
 fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>)) {
     log.log_mem("values loaded").log_time("values loaded");
     {
@@ -222,6 +314,192 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
             log.log_mem("initialized").log_time("initialized");
 
             query(&|q| vec_search::mixed_search_pred(&values, q, args.min_start_level), &args, &mut log);
+        } else if args.implementation >= 103 && args.implementation <= 106 { //search baselines on u40 (-u) or usize
+            macro_rules! vecsearch {
+                (  $values:expr ) => {
+                    {
+                        let values = $values;
+
+                        //print stats
+                        log.print_result(format!("level=-1\telements={}", values.len()));
+                        log.log_mem("initialized").log_time("initialized");
+
+                        match args.implementation {
+                            103 => query(&|q| vec_search::interpolation_search_pred(&values, q), &args, &mut log),
+                            104 => query(&|q| vec_search::interpolation_sequential_search_pred(&values, q), &args, &mut log),
+                            105 => {
+                                let hint = Cell::new(0);
+                                query(&|q| vec_search::exponential_search_pred(&values, q, &hint), &args, &mut log)
+                            }
+                            _ => query(&|q| vec_search::branchless_bin_search_pred(&values, q), &args, &mut log),
+                        }
+                    }
+                };
+            }
+
+            if args.u40 {
+                vecsearch!(get_u40_values(values));
+            } else {
+                vecsearch!(get_usize_values(values));
+            }
+        } else if args.implementation == 107 { //van emde boas tree on u40 (-u) or usize
+            macro_rules! vebtest {
+                (  $values:expr; $t:ty; $bits:expr ) => {
+                    {
+                        let values: Vec<$t> = $values;
+                        //print stats
+                        log.print_result(format!("level=-1\telements={}", values.len()));
+                        let mut veb = veb::VEB::new($bits);
+                        for value in values {
+                            PredecessorSet::<$t>::insert(&mut veb, value);
+                        }
+                        log.log_mem("initialized").log_time("initialized");
+
+                        query(&|q: $t| PredecessorSet::<$t>::predecessor(&veb, q), &args, &mut log);
+                        if args.memory {
+                            log.print_result(format!("memory=total\tbytes={}", veb.heap_size()));
+                        }
+
+                        if let Some(ref file) = args.add {
+                            let new_values: Vec<usize> = nmbrsrc::load(file.to_str().unwrap()).unwrap();
+                            log.log_mem("Values to add loaded").log_time("Values to add loaded");
+                            for value in new_values {
+                                PredecessorSet::<$t>::insert(&mut veb, <$t>::from(value));
+                            }
+                            log.log_mem("Values added").log_time("Values added");
+
+                            query(&|q: $t| PredecessorSet::<$t>::predecessor(&veb, q), &args, &mut log);
+                        }
+
+                        if let Some(ref file) = args.delete {
+                            let values_to_remove: Vec<usize> = nmbrsrc::load(file.to_str().unwrap()).unwrap();
+                            log.log_mem("Values to remove loaded").log_time("Values to remove loaded");
+                            for value in values_to_remove {
+                                PredecessorSet::<$t>::delete(&mut veb, <$t>::from(value));
+                            }
+                            log.log_mem("Values removed").log_time("Values removed");
+
+                            query(&|q: $t| PredecessorSet::<$t>::predecessor(&veb, q), &args, &mut log);
+                        }
+                    }
+                };
+            }
+
+            if args.u40 {
+                vebtest!(get_u40_values(values); u40; 40);
+            } else {
+                vebtest!(get_usize_values(values); usize; 64);
+            }
+        } else if args.implementation == 108 { //x-fast trie
+            let values = get_u40_values(values);
+            //print stats
+            log.print_result(format!("level=-1\telements={}", values.len()));
+            let mut xft = xft40_fnv::XFT::from_elements(values, &mut log);
+            log.log_mem("initialized").log_time("initialized");
+
+            query(&|q| xft.predecessor(q), &args, &mut log);
+            if args.memory {
+                xft.print_stats(&log);
+            }
+
+            if let Some(ref file) = args.add {
+                let new_values: Vec<u40> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect();
+                log.log_mem("Values to add loaded").log_time("Values to add loaded");
+                for value in new_values {
+                    xft.insert(value);
+                }
+                log.log_mem("Values added").log_time("Values added");
+
+                query(&|q| xft.predecessor(q), &args, &mut log);
+            }
+
+            if let Some(ref file) = args.delete {
+                let values_to_remove: Vec<u40> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect();
+                log.log_mem("Values to remove loaded").log_time("Values to remove loaded");
+                for value in values_to_remove {
+                    xft.delete(value);
+                }
+                log.log_mem("Values removed").log_time("Values removed");
+
+                query(&|q| xft.predecessor(q), &args, &mut log);
+            }
+        } else if args.implementation == 109 { //z-fast trie on u40 (-u) or usize
+            macro_rules! zfttest {
+                (  $values:expr; $t:ty; $bits:expr ) => {
+                    {
+                        let values: Vec<u64> = $values.iter().map(|v: &$t| v.to_u64()).collect();
+                        //print stats
+                        log.print_result(format!("level=-1\telements={}", values.len()));
+                        let zft = zft64_fnv::ZFT::new(values, $bits, &args, &mut log);
+                        log.log_mem("initialized").log_time("initialized");
+
+                        if args.search_stats {
+                            let file = args.queries.as_ref().expect("search stats requires query file (-q)");
+                            let test_values: Vec<u64> = load_queries(file, args).iter().map(|v| *v as u64).collect();
+                            let number = test_values.len();
+                            log.log_time(&format!("queries loaded\tqueries={}", number));
+                            //exit levels are lss levels (42 = top) like in the y-fast tries, with more than 42 prefix bits they go up to 64
+                            let mut stats = vec![vec![0; 65]; 65];
+                            let mut hit_count = 0;
+                            let mut miss_count = 0;
+                            let _: Vec<u64> = test_values.into_iter().map(|v| {
+                                let (r, e, c, m) = zft.predecessor_with_stats_u64(v);
+                                stats[e as usize][c as usize] += 1;
+                                hit_count += c - m;
+                                miss_count += m;
+                                r.unwrap_or(0)
+                            }).collect();
+                            log.log_time(&format!("queries processed\tnumber={}", number));
+                            print_search_stats(&stats, &log);
+                            log.print_result(format!("Hits={}\tMisses={}\tTotal={}", hit_count, miss_count, hit_count + miss_count));
+                        } else {
+                            query(&|q: $t| zft.predecessor_u64(q.to_u64()).map(|v| <$t>::from(v as usize)), &args, &mut log);
+                        }
+                        if args.memory {
+                            zft.print_stats(&log);
+                        }
+                    }
+                };
+            }
+
+            if args.u40 {
+                zfttest!(get_u40_values(values); u40; 40);
+            } else {
+                zfttest!(get_usize_values(values); usize; 64);
+            }
+        } else if args.implementation == 110 { //b+-tree with packed 40 bit keys
+            let values = get_u40_values(values);
+            //print stats
+            log.print_result(format!("level=-1\telements={}", values.len()));
+            let mut tree = btree40::BTree::from_sorted(values, &mut log);
+            log.log_mem("initialized").log_time("initialized");
+
+            query(&|q| tree.predecessor(q), &args, &mut log);
+            if args.memory {
+                tree.print_stats(&log);
+            }
+
+            if let Some(ref file) = args.add {
+                let new_values: Vec<u40> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect();
+                log.log_mem("Values to add loaded").log_time("Values to add loaded");
+                for value in new_values {
+                    tree.insert(value);
+                }
+                log.log_mem("Values added").log_time("Values added");
+
+                query(&|q| tree.predecessor(q), &args, &mut log);
+            }
+
+            if let Some(ref file) = args.delete {
+                let values_to_remove: Vec<u40> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect();
+                log.log_mem("Values to remove loaded").log_time("Values to remove loaded");
+                for value in values_to_remove {
+                    tree.delete(value);
+                }
+                log.log_mem("Values removed").log_time("Values removed");
+
+                query(&|q| tree.predecessor(q), &args, &mut log);
+            }
         } else if args.implementation == 200 { //test hashmap hit/miss time
             let values = get_u40_values(values);
             let mut map: FnvHashSet<u40> = FnvHashSet::from_iter(values.iter().map(|v| v.clone()));
@@ -254,9 +532,42 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
 
             if args.search_stats {
                 if let Some(ref file) = args.queries {
+                    //macro to evaluate search steps of yfts based on yft40so_generic_binsearch
+                    macro_rules! searchstatsyft40 {
+                        (  $yft:ty; $values:expr ) => {
+                            searchstatsyft40!($yft; $values; |_yft: &mut $yft| {})
+                        };
+                        (  $yft:ty; $values:expr; $prepare:expr ) => {
+                            {
+                                let mut yft = <$yft>::new($values, &args, &mut log);
+                                $prepare(&mut yft);
+                                let test_values: Vec<u40> = load_queries(file, args).iter().map(|v| u40::from(*v)).collect();
+                                let number = test_values.len();
+                                log.log_time(&format!("queries loaded\tqueries={}", number));
+                                let mut stats = vec![vec![0; 44]; 44];
+                                let mut hit_count = 0;
+                                let mut miss_count = 0;
+                                let mut lookup_stats = LookupStats::default();
+                                let _: Vec<u40> = test_values.into_iter().map(|v| {
+                                    let (r, e, c, m) = yft.predecessor_with_stats(v, &mut lookup_stats);
+                                    stats[e as usize][c as usize] += 1;
+                                    hit_count += c - m;
+                                    miss_count += m;
+                                    r.unwrap_or(u40::from(0))
+                                }).collect();
+                                log.log_time(&format!("queries processed\tnumber={}", number));
+                                print_search_stats(&stats, &log);
+                                log.print_result(format!("Hits={}\tMisses={}\tTotal={}\tProbes={}\tFalsePositives={}\tAvoidedLookups={}", hit_count, miss_count, hit_count + miss_count, lookup_stats.probes, lookup_stats.false_positives, lookup_stats.avoided_lookups));
+                                if args.memory {
+                                    yft.print_stats(&log);
+                                }
+                            }
+                        };
+                    }
+
                     if args.implementation == 12 {
                         let yft = yft40sn_bin_fnv::YFT::new(values, &args, &mut log);
-                        let test_values: Vec<u40> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect();
+                        let test_values: Vec<u40> = load_queries(file, args).iter().map(|v| u40::from(*v)).collect();
                         let number = test_values.len();
                         log.log_time(&format!("queries loaded\tqueries={}", number));
                         let mut stats = vec![vec![0; 44]; 44];
@@ -270,20 +581,14 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
                             r.unwrap_or(u40::from(0))
                         }).collect();
                         log.log_time(&format!("queries processed\tnumber={}", number));
-                        for e in 0..43 {
-                            for c in 0..43 {
-                                if stats[e][c] > 0 {
-                                    log.print_result(format!("Exit={}\tSearchSteps={}\tfrequency={}", e, c, stats[e][c]));
-                                }
-                            }
-                        }
+                        print_search_stats(&stats, &log);
                         log.print_result(format!("Hits={}\tMisses={}\tTotal={}", hit_count, miss_count, hit_count + miss_count));
                         if args.memory {
                             yft.print_stats(&log);
                         }
                     } else if args.implementation == 23 {
                         let yft = yft40so_fnv_bin_weight::YFT::new(values, &args, &mut log);
-                        let test_values: Vec<u40> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect();
+                        let test_values: Vec<u40> = load_queries(file, args).iter().map(|v| u40::from(*v)).collect();
                         let number = test_values.len();
                         log.log_time(&format!("queries loaded\tqueries={}", number));
                         let mut stats = vec![vec![0; 44]; 44];
@@ -297,20 +602,14 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
                             r.unwrap_or(u40::from(0))
                         }).collect();
                         log.log_time(&format!("queries processed\tnumber={}", number));
-                        for e in 0..43 {
-                            for c in 0..43 {
-                                if stats[e][c] > 0 {
-                                    log.print_result(format!("Exit={}\tSearchSteps={}\tfrequency={}", e, c, stats[e][c]));
-                                }
-                            }
-                        }
+                        print_search_stats(&stats, &log);
                         log.print_result(format!("Hits={}\tMisses={}\tTotal={}", hit_count, miss_count, hit_count + miss_count));
                         if args.memory {
                             yft.print_stats(&log);
                         }
                     } else if args.implementation == 29 {
                         let yft = yft40so_fnv_bin_weight::YFT::new(values, &args, &mut log);
-                        let test_values: Vec<u40> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect();
+                        let test_values: Vec<u40> = load_queries(file, args).iter().map(|v| u40::from(*v)).collect();
                         let number = test_values.len();
                         log.log_time(&format!("queries loaded\tqueries={}", number));
                         let mut stats = vec![vec![0; 44]; 44];
@@ -324,20 +623,14 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
                             r.unwrap_or(u40::from(0))
                         }).collect();
                         log.log_time(&format!("queries processed\tnumber={}", number));
-                        for e in 0..43 {
-                            for c in 0..43 {
-                                if stats[e][c] > 0 {
-                                    log.print_result(format!("Exit={}\tSearchSteps={}\tfrequency={}", e, c, stats[e][c]));
-                                }
-                            }
-                        }
+                        print_search_stats(&stats, &log);
                         log.print_result(format!("Hits={}\tMisses={}\tTotal={}", hit_count, miss_count, hit_count + miss_count));
                         if args.memory {
                             yft.print_stats(&log);
                         }
                     } else if args.implementation == 30 {
                         let yft = yft40so_fnv_small_groups::YFT::new(values, &args, &mut log);
-                        let test_values: Vec<u40> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect();
+                        let test_values: Vec<u40> = load_queries(file, args).iter().map(|v| u40::from(*v)).collect();
                         let number = test_values.len();
                         log.log_time(&format!("queries loaded\tqueries={}", number));
                         let mut stats = vec![vec![0; 44]; 44];
@@ -351,19 +644,45 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
                             r.unwrap_or(u40::from(0))
                         }).collect();
                         log.log_time(&format!("queries processed\tnumber={}", number));
-                        for e in 0..43 {
-                            for c in 0..43 {
-                                if stats[e][c] > 0 {
-                                    log.print_result(format!("Exit={}\tSearchSteps={}\tfrequency={}", e, c, stats[e][c]));
-                                }
-                            }
-                        }
+                        print_search_stats(&stats, &log);
                         log.print_result(format!("Hits={}\tMisses={}\tTotal={}", hit_count, miss_count, hit_count + miss_count));
                         if args.memory {
                             yft.print_stats(&log);
                         }
+                    } else if args.implementation == 21 {
+                        searchstatsyft40!(yft40so_fx_hash_binsearch::YFT; values);
+                    } else if args.implementation == 24 {
+                        searchstatsyft40!(yft40so_rust_hash_binsearch::YFT; values);
+                    } else if args.implementation == 25 {
+                        searchstatsyft40!(yft40so_boomphf_binsearch::YFT; values);
+                    } else if args.implementation == 26 {
+                        searchstatsyft40!(yft40so_boomphf_para_binsearch::YFT; values);
+                    } else if args.implementation == 28 {
+                        searchstatsyft40!(yft40so_im_binsearch::YFT; values);
+                    } else if args.implementation == 31 {
+                        searchstatsyft40!(yft40so_hash_brown_binsearch::YFT; values);
+                    } else if args.implementation == 32 {
+                        searchstatsyft40!(yft40so_packed_binsearch::YFT; values);
+                    } else if args.implementation == 33 {
+                        searchstatsyft40!(yft40so_mphf_binsearch::YFT; values);
+                    } else if args.implementation == 34 {
+                        searchstatsyft40!(yft40so_fnv_bloom_binsearch::YFT; values);
+                    } else if args.implementation == 35 {
+                        searchstatsyft40!(yft40so_fnv_eytzinger_binsearch::YFT; values);
+                    } else if args.implementation == 36 {
+                        searchstatsyft40!(yft40so_fnv_block8_binsearch::YFT; values);
+                    } else if args.implementation == 37 {
+                        searchstatsyft40!(yft40so_fnv_block16_binsearch::YFT; values);
+                    } else if args.implementation == 38 {
+                        searchstatsyft40!(yft40so_fnv_elias_fano_binsearch::YFT; values);
+                    } else if args.implementation == 39 {
+                        searchstatsyft40!(yft40so_fnv_interpolation_binsearch::YFT; values);
+                    } else if args.implementation == 40 {
+                        searchstatsyft40!(yft40so_fnv_linear_model_binsearch::YFT; values);
+                    } else if args.implementation == 41 {
+                        searchstatsyft40!(yft40so_fnv_trained_binsearch::YFT; values; |yft: &mut yft40so_fnv_trained_binsearch::YFT| train_level_search(yft, args.level_training_file.as_ref().expect("search stats of h = 41 require training queries (--level-training-file)"), args, &mut log));
                     } else {
-                        panic!("search stats can not be made with -h {}, use 12, 23, 29 or 30", args.implementation);
+                        panic!("search stats can not be made with -h {}, use 12, 21, 23 - 26 or 28 - 41", args.implementation);
                     }
                 } else {
                     panic!("search stats requires query file (-q)");
@@ -372,8 +691,15 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
                 //macro to load & test yft
                 macro_rules! testyft40 {
                     (  $yft:ty; $values:expr ) => {
+                        testyft40!($yft; $values; new)
+                    };
+                    (  $yft:ty; $values:expr; $constructor:ident ) => {
+                        testyft40!($yft; $values; $constructor; |_yft: &mut $yft| {})
+                    };
+                    (  $yft:ty; $values:expr; $constructor:ident; $prepare:expr ) => {
                         {
-                            let yft =  <$yft>::new($values, &args, &mut log);
+                            let mut yft =  <$yft>::$constructor($values, &args, &mut log);
+                            $prepare(&mut yft);
 
                             log.log_mem("initialized").log_time("initialized");
 
@@ -479,16 +805,31 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
                         }
                     }
                     20 => testyft40!(yft40bo_fx_hash::YFT; values),
-                    21 => testyft40!(yft40so_fx_hash_binsearch::YFT; values),
+                    21 => testyft40!(yft40so_fx_hash_binsearch::YFT; values; new_parallel),
                     22 => testyft40!(yft40so_fx_hash_linsearch::YFT; values),
-                    23 => testyft40!(yft40so_fnv_binsearch::YFT; values),
-                    24 => testyft40!(yft40so_rust_hash_binsearch::YFT; values),
-                    25 => testyft40!(yft40so_boomphf_binsearch::YFT; values),
-                    26 => testyft40!(yft40so_boomphf_para_binsearch::YFT; values),
+                    23 => testyft40!(yft40so_fnv_binsearch::YFT; values; new_parallel),
+                    24 => testyft40!(yft40so_rust_hash_binsearch::YFT; values; new_parallel),
+                    25 => testyft40!(yft40so_boomphf_binsearch::YFT; values; new_parallel),
+                    26 => testyft40!(yft40so_boomphf_para_binsearch::YFT; values; new_parallel),
                     27 => testyft40!(yft40so_fx_hash_small_groups::YFT; values),
                     28 => testyft40!(yft40so_im_binsearch::YFT; values),
                     29 => testyft40!(yft40so_fnv_bin_weight::YFT; values),
                     30 => testyft40!(yft40so_fnv_small_groups::YFT; values),
+                    31 => testyft40!(yft40so_hash_brown_binsearch::YFT; values; new_parallel),
+                    32 => testyft40!(yft40so_packed_binsearch::YFT; values; new_parallel),
+                    33 => testyft40!(yft40so_mphf_binsearch::YFT; values; new_parallel),
+                    34 => testyft40!(yft40so_fnv_bloom_binsearch::YFT; values; new_parallel),
+                    35 => testyft40!(yft40so_fnv_eytzinger_binsearch::YFT; values; new_parallel),
+                    36 => testyft40!(yft40so_fnv_block8_binsearch::YFT; values; new_parallel),
+                    37 => testyft40!(yft40so_fnv_block16_binsearch::YFT; values; new_parallel),
+                    38 => testyft40!(yft40so_fnv_elias_fano_binsearch::YFT; values; new_parallel),
+                    39 => testyft40!(yft40so_fnv_interpolation_binsearch::YFT; values; new_parallel),
+                    40 => testyft40!(yft40so_fnv_linear_model_binsearch::YFT; values; new_parallel),
+                    41 => testyft40!(yft40so_fnv_trained_binsearch::YFT; values; new_parallel; |yft: &mut yft40so_fnv_trained_binsearch::YFT| {
+                        if let Some(ref file) = args.level_training_file {
+                            train_level_search(yft, file, args, &mut log);
+                        }
+                    }),
                     _ => panic!("Invalid input for argument hash_map")
                 }
             }
@@ -503,7 +844,7 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
             //load queries & apply them, if option is set
             if let Some(ref file) = args.queries {
                 if args.search_stats {
-                    let test_values = nmbrsrc::load(file.to_str().unwrap()).unwrap();
+                    let test_values = load_queries(file, args).to_vec();
                     let number = test_values.len();
                     log.log_time(&format!("queries loaded\tqueries={}", number));
                     let mut stats = vec![vec![0; 44]; 44];
@@ -517,13 +858,7 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
                         r.unwrap_or(0)
                     }).collect();
                     log.log_time(&format!("queries processed\tnumber={}", number));
-                    for e in 0..43 {
-                        for c in 0..43 {
-                            if stats[e][c] > 0 {
-                                log.print_result(format!("Exit={}\tSearchSteps={}\tfrequency={}", e, c, stats[e][c]));
-                            }
-                        }
-                    }
+                    print_search_stats(&stats, &log);
                     log.print_result(format!("Hits={}\tMisses={}\tTotal={}", hit_count, miss_count, hit_count + miss_count));
                     if args.memory {
                         yft.print_stats(&log);
@@ -540,10 +875,75 @@ fn run_yft(args: &Args, mut log: &mut log::Log, values: (Vec<usize>, Vec<u40>))
     }
 }
 
+/// builds the yft directly from the input file, without storing the input
+fn run_stream(args: &Args, mut log: &mut log::Log) {
+    if args.implementation != 16 {
+        panic!("--stream works only with -h 16");
+    }
+    let yft = match &args.values {
+        ValueSrc::U40 { path } => {
+            yft40_split_small::YFT::from_sorted_iter(nmbrsrc::stream_u40_fit(path.to_str().unwrap()).unwrap().map(|v| v.unwrap()), &args, &mut log)
+        }
+        ValueSrc::U40T { path } => {
+            yft40_split_small::YFT::from_sorted_iter(nmbrsrc::stream_u40_tim(path.to_str().unwrap()).unwrap().map(|v| v.unwrap()), &args, &mut log)
+        }
+        ValueSrc::U64T { path } => {
+            yft40_split_small::YFT::from_sorted_iter(nmbrsrc::stream_u64_tim(path.to_str().unwrap()).unwrap().map(|v| {
+                let v = v.unwrap();
+                assert!(v < 1 << 40, "Value {} does not fit into 40 bit", v);
+                u40::from(v)
+            }), &args, &mut log)
+        }
+        _ => panic!("--stream works only with U40, U40T or U64T input")
+    };
+
+    log.log_mem("initialized").log_time("initialized");
+
+    query(&|q| yft.predecessor(q), &args, &mut log);
+    if args.memory {
+        yft.print_stats(&log);
+    }
+}
+
+thread_local! {
+    //queries of the element length test, they are loaded once and used for every run
+    static QUERY_CACHE: RefCell<Option<Rc<Vec<usize>>>> = RefCell::new(None);
+}
+
+/// loads the query file, during the element length test it is read from disk only once
+fn load_queries(file: &PathBuf, args: &Args) -> Rc<Vec<usize>> {
+    if !args.element_length_test {
+        return Rc::new(nmbrsrc::load(file.to_str().unwrap()).unwrap());
+    }
+    QUERY_CACHE.with(|cache| {
+        cache.borrow_mut().get_or_insert_with(|| Rc::new(nmbrsrc::load(file.to_str().unwrap()).unwrap())).clone()
+    })
+}
+
+/// trains the level search of h = 41 with evenly spaced queries of the training file
+fn train_level_search(yft: &mut yft40so_fnv_trained_binsearch::YFT, file: &PathBuf, args: &Args, log: &mut log::Log) {
+    assert!(args.queries.as_ref() != Some(file), "--level-training-file must differ from the query file (-q)");
+    let queries: Vec<u40> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| u40::from(v)).collect();
+    let sample = nmbrsrc::subsample(&queries, args.level_training_queries, None);
+    yft.train_level_search(&sample);
+    log.log_time(&format!("level search trained\tqueries={}", sample.len()));
+}
+
+/// prints the number of queries per exit level and number of search steps (stats[exit][steps])
+fn print_search_stats(stats: &Vec<Vec<usize>>, log: &log::Log) {
+    for (e, steps) in stats.iter().enumerate() {
+        for (c, frequency) in steps.iter().enumerate() {
+            if *frequency > 0 {
+                log.print_result(format!("Exit={}\tSearchSteps={}\tfrequency={}", e, c, frequency));
+            }
+        }
+    }
+}
+
 //load queries & apply them, if option is set
 fn query<T: From<usize> + std::fmt::Debug>(f: &dyn Fn(T) -> Option<T>, args: &Args, log: &mut log::Log) {
     if let Some(ref file) = args.queries {
-        let queries: Vec<T> = nmbrsrc::load(file.to_str().unwrap()).unwrap().into_iter().map(|v| T::from(v)).collect();
+        let queries: Vec<T> = load_queries(file, args).iter().map(|v| T::from(*v)).collect();
         let number = queries.len();
         log.log_time(&format!("queries loaded\tqueries={}", number));
         if args.result {
@@ -559,6 +959,51 @@ fn query<T: From<usize> + std::fmt::Debug>(f: &dyn Fn(T) -> Option<T>, args: &Ar
     }
 }
 
+/// saves the input in the given format, regardless whether it was created as u64 or u40
+fn store_values(values: &(Vec<usize>, Vec<u40>), path: &str, format: StoreFormat, compression: Compression) -> std::io::Result<()> {
+    let is_u40 = values.0.len() == 0;
+    match format {
+        StoreFormat::Load => {
+            if is_u40 {
+                nmbrsrc::save(&values.1.iter().map(|v| usize::from(*v)).collect(), path, compression)
+            } else {
+                nmbrsrc::save(&values.0, path, compression)
+            }
+        }
+        StoreFormat::U64S => {
+            if is_u40 {
+                nmbrsrc::save_u64_serialized(&values.1.iter().map(|v| usize::from(*v)).collect(), path, compression)
+            } else {
+                nmbrsrc::save_u64_serialized(&values.0, path, compression)
+            }
+        }
+        StoreFormat::U64T => {
+            if is_u40 {
+                nmbrsrc::save_u64_tim(&values.1.iter().map(|v| usize::from(*v)).collect(), path, compression)
+            } else {
+                nmbrsrc::save_u64_tim(&values.0, path, compression)
+            }
+        }
+        StoreFormat::U40 | StoreFormat::U40S | StoreFormat::U40T => {
+            let converted: Vec<u40>;
+            let u40_values = if is_u40 {
+                &values.1
+            } else {
+                if values.0.iter().any(|v| *v > 1099511627775) {
+                    return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "values do not fit into 40 bit"));
+                }
+                converted = values.0.iter().map(|v| u40::from(*v)).collect();
+                &converted
+            };
+            match format {
+                StoreFormat::U40 => nmbrsrc::save_u40_fit(u40_values, path, compression),
+                StoreFormat::U40S => nmbrsrc::save_u40_serialized(u40_values, path, compression),
+                _ => nmbrsrc::save_u40_tim(u40_values, path, compression),
+            }
+        }
+    }
+}
+
 fn get_u40_values(values: (Vec<usize>, Vec<u40>)) -> Vec<u40> {
     if values.0.len() == 0 {
         values.1
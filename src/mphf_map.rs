@@ -0,0 +1,149 @@
+extern crate boomphf;
+
+/// this module contains a static hash table for the lss levels of the tries, based on a minimal perfect hash function
+/// a mphf maps absent keys to arbitrary slots, so a slot has to be verified
+/// to avoid reading the full key on most misses, a small fingerprint per slot is checked first
+
+use std::mem::size_of;
+use uint::u40;
+use level_map::{LevelMap, LevelMapConfig, LookupStats};
+use self::boomphf::Mphf;
+
+pub type DataType = u40;
+
+/// fingerprint size, if none is given
+pub const DEFAULT_FINGERPRINT_BITS: usize = 8;
+/// space factor of the mphf (boomphf recommends 1.7)
+const GAMMA: f64 = 1.7;
+/// independent of the mphf hash, so fingerprints of colliding keys differ
+const FINGERPRINT_MULTIPLIER: u64 = 0xC2B2_AE3D_27D4_EB4F;
+
+/// static map from u40 to u40
+pub struct MphfMap {
+    mphf: Mphf<DataType>,
+    // fingerprint_bits per slot, packed
+    fingerprints: Vec<u64>,
+    fingerprint_bits: usize,
+    keys: Vec<DataType>,
+    values: Vec<DataType>,
+}
+
+impl MphfMap {
+    /// keys must be unique, values[i] belongs to keys[i]
+    /// fingerprint_bits = bits per key that are checked before the key itself (0 to 32)
+    pub fn with_fingerprint_bits(keys: Vec<DataType>, values: Vec<DataType>, fingerprint_bits: usize) -> MphfMap {
+        debug_assert!(keys.len() == values.len());
+        assert!(fingerprint_bits <= 32, "fingerprints may have at most 32 bits");
+        let mphf = Mphf::new(GAMMA, &keys);
+        let len = keys.len();
+        let mut map = MphfMap {
+            mphf,
+            fingerprints: vec![0; (len * fingerprint_bits + 63) / 64],
+            fingerprint_bits,
+            keys: vec![DataType::from(0u64); len],
+            values: vec![DataType::from(0u64); len],
+        };
+        for (key, value) in keys.into_iter().zip(values.into_iter()) {
+            let slot = map.mphf.hash(&key) as usize;
+            let fingerprint = map.fingerprint(key);
+            map.set_fingerprint(slot, fingerprint);
+            map.keys[slot] = key;
+            map.values[slot] = value;
+        }
+        map
+    }
+
+    #[inline]
+    fn fingerprint(&self, key: DataType) -> u64 {
+        if self.fingerprint_bits == 0 {
+            return 0;
+        }
+        u64::from(key).wrapping_mul(FINGERPRINT_MULTIPLIER) >> (64 - self.fingerprint_bits)
+    }
+
+    fn set_fingerprint(&mut self, slot: usize, fingerprint: u64) {
+        let bit = slot * self.fingerprint_bits;
+        for i in 0..self.fingerprint_bits {
+            if (fingerprint >> i) & 1 == 1 {
+                self.fingerprints[(bit + i) / 64] |= 1 << ((bit + i) % 64);
+            }
+        }
+    }
+
+    #[inline]
+    fn get_fingerprint(&self, slot: usize) -> u64 {
+        if self.fingerprint_bits == 0 {
+            return 0;
+        }
+        let bit = slot * self.fingerprint_bits;
+        let word = bit / 64;
+        let offset = bit % 64;
+        let mask = (1u64 << self.fingerprint_bits) - 1;
+        unsafe {
+            let mut fingerprint = *self.fingerprints.get_unchecked(word) >> offset;
+            if offset + self.fingerprint_bits > 64 {
+                //fingerprint continues in next word
+                fingerprint |= *self.fingerprints.get_unchecked(word + 1) << (64 - offset);
+            }
+            fingerprint & mask
+        }
+    }
+
+    /// value of key + number of probes (1 = fingerprint compared, 2 = fingerprint matched and key compared)
+    #[inline]
+    fn lookup(&self, key: &DataType) -> (Option<&DataType>, usize) {
+        let slot = match self.mphf.try_hash(key) {
+            Some(slot) => slot as usize,
+            None => return (None, 0),
+        };
+        if slot >= self.keys.len() {
+            return (None, 0);
+        }
+        if self.get_fingerprint(slot) != self.fingerprint(*key) {
+            return (None, 1);
+        }
+        unsafe {
+            if self.keys.get_unchecked(slot) == key {
+                (Some(self.values.get_unchecked(slot)), 2)
+            } else {
+                (None, 2)
+            }
+        }
+    }
+}
+
+impl LevelMap<DataType, DataType> for MphfMap {
+    fn from_sorted_pairs(keys: Vec<DataType>, values: Vec<DataType>) -> Self {
+        MphfMap::with_fingerprint_bits(keys, values, DEFAULT_FINGERPRINT_BITS)
+    }
+    fn from_sorted_pairs_with_config(keys: Vec<DataType>, values: Vec<DataType>, config: &LevelMapConfig) -> Self {
+        MphfMap::with_fingerprint_bits(keys, values, config.fingerprint_bits)
+    }
+    #[inline]
+    fn get(&self, key: &DataType) -> Option<&DataType> {
+        self.lookup(key).0
+    }
+    #[inline]
+    fn get_with_stats(&self, key: &DataType, stats: &mut LookupStats) -> Option<&DataType> {
+        let (value, probes) = self.lookup(key);
+        stats.probes += probes;
+        //the fingerprint matched, but the key was different
+        if value.is_none() && probes == 2 {
+            stats.false_positives += 1;
+        }
+        value
+    }
+    fn len(&self) -> usize {
+        self.keys.len()
+    }
+    /// mphf size is estimated with 3 bits per key
+    fn heap_size(&self) -> usize {
+        self.keys.len() * 3 / 8 + self.fingerprints.capacity() * size_of::<u64>() + (self.keys.capacity() + self.values.capacity()) * size_of::<DataType>()
+    }
+    fn heap_size_is_estimated(&self) -> bool {
+        true
+    }
+    fn estimate_heap_size(len: usize, config: &LevelMapConfig) -> usize {
+        len * 3 / 8 + (len * config.fingerprint_bits + 63) / 64 * size_of::<u64>() + len * 2 * size_of::<DataType>()
+    }
+}
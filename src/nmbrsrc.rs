@@ -2,17 +2,25 @@ extern crate rand;
 extern crate rand_distr;
 extern crate serde;
 extern crate rmp_serde as rmps;
+extern crate flate2;
+extern crate zstd;
 
 /// this module is used to generate, save and load vectors of numbers
 
-use self::rand::{distributions::Uniform, Rng};
+use self::rand::{distributions::Uniform, Rng, SeedableRng};
+use self::rand::rngs::StdRng;
+use self::rand::seq::index;
 use self::rand_distr::{Poisson, Normal, Distribution};
 use std::fs::File;
+use std::collections::HashSet;
 use self::serde::{Serialize, Deserialize};
 use self::rmps::{Serializer, Deserializer};
 use uint::u40;
-use std::io::{BufReader, BufWriter};
-use std::io::Read;
+use std::io::{BufRead, BufReader, BufWriter};
+use std::io::{Read, Write};
+use self::flate2::read::MultiGzDecoder;
+use self::flate2::write::GzEncoder;
+use args::Compression;
 
 /// length = number of elements in result
 /// mean = mean point of distribution
@@ -78,32 +86,270 @@ pub fn get_uniform_dist_restricted(length: usize, min_value: usize, max_value: u
     rand::thread_rng().sample_iter(Uniform::from(min_value..max_value)).take(length).collect()
 }
 
+/// zstd compression level used for writing
+const ZSTD_LEVEL: i32 = 3;
+
+/// output file, that may be compressed
+/// finish has to be called, after everything has been written
+pub enum Output {
+    Plain(BufWriter<File>),
+    Gzip(GzEncoder<BufWriter<File>>),
+    Zstd(zstd::stream::write::Encoder<BufWriter<File>>),
+}
+
+impl Output {
+    pub fn create(path: &str, compression: Compression) -> std::io::Result<Output> {
+        let file = BufWriter::new(File::create(path)?);
+        Ok(match compression {
+            Compression::None => Output::Plain(file),
+            Compression::Gzip => Output::Gzip(GzEncoder::new(file, flate2::Compression::default())),
+            Compression::Zstd => Output::Zstd(zstd::stream::write::Encoder::new(file, ZSTD_LEVEL)?),
+        })
+    }
+
+    /// writes compression trailers and flushes the file
+    pub fn finish(self) -> std::io::Result<()> {
+        match self {
+            Output::Plain(mut file) => file.flush(),
+            Output::Gzip(encoder) => encoder.finish()?.flush(),
+            Output::Zstd(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            Output::Plain(file) => file.write(buf),
+            Output::Gzip(encoder) => encoder.write(buf),
+            Output::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            Output::Plain(file) => file.flush(),
+            Output::Gzip(encoder) => encoder.flush(),
+            Output::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// compression of the input by its magic bytes (1f 8b = gzip, 28 b5 2f fd = zstd), the input is not consumed
+/// everything else is read as uncompressed, independent of the file extension
+fn detect_compression<R: BufRead>(input: &mut R) -> std::io::Result<Compression> {
+    let head = input.fill_buf()?;
+    Ok(if head.starts_with(&[0x1f, 0x8b]) {
+        Compression::Gzip
+    } else if head.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+        Compression::Zstd
+    } else {
+        Compression::None
+    })
+}
+
+/// opens a file for reading and decompresses it, if it is gzip or zstd compressed
+/// returns the reader and the detected compression
+pub fn open_input(path: &str) -> std::io::Result<(Box<dyn Read>, Compression)> {
+    let mut input = BufReader::new(File::open(path)?);
+    let compression = detect_compression(&mut input)?;
+    let reader: Box<dyn Read> = match compression {
+        Compression::None => Box::new(input),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(input))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::stream::read::Decoder::new(input)?)),
+    };
+    Ok((reader, compression))
+}
+
+/// draws values with sampler until there are length different ones
+/// result will be ordered
+fn get_unique_values<F: FnMut() -> usize>(length: usize, mut sampler: F) -> Vec<usize> {
+    let mut set = HashSet::with_capacity(length);
+    while set.len() < length {
+        set.insert(sampler());
+    }
+    let mut vec: Vec<usize> = set.into_iter().collect();
+    vec.sort();
+    vec
+}
+
+/// IPv4 addresses (32 bit), that are grouped into randomly placed prefix blocks like routed networks
+/// length = number of elements in result
+/// blocks = number of prefix blocks
+/// min_prefix, max_prefix = range of the prefix lengths of the blocks (e.g. 16 and 24)
+/// result will be ordered
+pub fn get_ipv4_dist(length: usize, blocks: usize, min_prefix: usize, max_prefix: usize) -> Vec<usize> {
+    assert!(blocks > 0 && min_prefix <= max_prefix && max_prefix <= 32);
+    let mut rng = rand::thread_rng();
+    //(first address, number of addresses)
+    let blocks: Vec<(usize, usize)> = (0..blocks).map(|_| {
+        let prefix = rng.gen_range(min_prefix, max_prefix + 1);
+        let block_size = 1usize << (32 - prefix);
+        let network: usize = rng.gen_range(0, 1usize << prefix);
+        (network << (32 - prefix), block_size)
+    }).collect();
+    //blocks can overlap, so only the addresses in their union count
+    let mut sorted_blocks = blocks.clone();
+    sorted_blocks.sort();
+    let mut addresses = 0;
+    let mut covered_until = 0;
+    for (network, block_size) in sorted_blocks {
+        let end = network + block_size;
+        if end > covered_until {
+            addresses += end - std::cmp::max(network, covered_until);
+            covered_until = end;
+        }
+    }
+    assert!(addresses >= length, "Blocks are to small for {} addresses", length);
+    get_unique_values(length, || {
+        let (network, block_size) = blocks[rng.gen_range(0, blocks.len())];
+        network + rng.gen_range(0, block_size)
+    })
+}
+
+/// event timestamps in milliseconds (40 bit), with an event rate that changes over the day
+/// length = number of elements in result
+/// days = time span of the events
+/// peak_factor = how much higher the rate at noon is than at midnight (at least 1)
+/// result will be ordered
+pub fn get_timestamp_dist(length: usize, days: usize, peak_factor: f64) -> Vec<usize> {
+    const MS_PER_DAY: usize = 86_400_000;
+    let span = days * MS_PER_DAY;
+    assert!(days > 0 && span <= 1099511627776, "Time span does not fit into 40 bit");
+    assert!(peak_factor >= 1., "peak factor must not be smaller than 1");
+    assert!(length <= span, "Time span is to small for {} timestamps", length);
+    let mut rng = rand::thread_rng();
+    get_unique_values(length, || {
+        //rejection sampling with rate(t) = 1 at midnight and peak_factor at noon
+        loop {
+            let timestamp: usize = rng.gen_range(0, span);
+            let day_phase = (timestamp % MS_PER_DAY) as f64 / MS_PER_DAY as f64;
+            let rate = 1. + (peak_factor - 1.) * (1. - (2. * std::f64::consts::PI * day_phase).cos()) / 2.;
+            if rng.gen::<f64>() * peak_factor < rate {
+                return timestamp;
+            }
+        }
+    })
+}
+
+/// sparse object ids (40 bit), with a shard id in the highest bits and ids, that were assigned with gaps within each shard
+/// length = number of elements in result
+/// shards = number of used shards
+/// shard_bits = number of high bits that are used for the shard id
+/// mean_gap = average distance between two ids of the same shard
+/// result will be ordered
+pub fn get_sparse_id_dist(length: usize, shards: usize, shard_bits: usize, mean_gap: usize) -> Vec<usize> {
+    assert!(shard_bits < 40 && shards > 0 && shards <= 1usize << shard_bits && mean_gap > 0);
+    let local_bits = 40 - shard_bits;
+    let ids_per_shard = (length + shards - 1) / shards;
+    assert!(ids_per_shard * 2 * mean_gap < 1usize << local_bits, "Ids do not fit below shard prefix, use smaller gaps or less shard bits");
+    let mut rng = rand::thread_rng();
+    //choose different shard prefixes
+    let shard_ids = get_unique_values(shards, || rng.gen_range(0, 1usize << shard_bits));
+    let mut vec = Vec::with_capacity(length);
+    for (i, shard) in shard_ids.iter().enumerate() {
+        let number = if i < length % shards || length % shards == 0 { ids_per_shard } else { ids_per_shard - 1 };
+        //leave enough space for all ids of this shard
+        let mut id = rng.gen_range(0, (1usize << local_bits) - number * 2 * mean_gap);
+        for _ in 0..number {
+            vec.push((shard << local_bits) | id);
+            id += rng.gen_range(1, 2 * mean_gap);
+        }
+    }
+    debug_assert!(vec.len() == length);
+    debug_assert!(vec.windows(2).all(|w| w[0] < w[1]));
+    vec
+}
+
+/// ordered subset of values with size elements
+/// seed = None takes evenly spaced elements, else a random subset is drawn
+pub fn subsample<T: Copy>(values: &Vec<T>, size: usize, seed: Option<u64>) -> Vec<T> {
+    if size >= values.len() {
+        return values.clone();
+    }
+    match seed {
+        None => (0..size).map(|i| values[i * values.len() / size]).collect(),
+        Some(seed) => {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut positions = index::sample(&mut rng, values.len(), size).into_vec();
+            positions.sort();
+            positions.into_iter().map(|i| values[i]).collect()
+        }
+    }
+}
+
 ///If File exists, values will be loaded & written sorted with new values. In this case.
 ///Else a new File will be created
-pub fn save(values: &Vec<usize>, path: &str) -> std::io::Result<()> {
+pub fn save(values: &Vec<usize>, path: &str, compression: Compression) -> std::io::Result<()> {
+    let mut output;
     if let Ok(mut old_values) = load(path) {
         old_values.append(&mut values.clone());
         old_values.sort(); //TODO theoretisch könnte man hier zeit sparen, wenn man ausnutzt, dass beide vektoren sortiert sind
-        let mut output = BufWriter::new(File::create(path)?);
-        Ok(old_values.serialize(&mut Serializer::new(&mut output)).unwrap())
+        output = Output::create(path, compression)?;
+        old_values.serialize(&mut Serializer::new(&mut output)).unwrap();
     } else {
-        let mut output = File::create(path)?;
-        Ok(values.serialize(&mut Serializer::new(&mut output)).unwrap())
+        output = Output::create(path, compression)?;
+        values.serialize(&mut Serializer::new(&mut output)).unwrap();
+    }
+    output.finish()
+}
+
+/// save u64 values serialized with rmp (readable with load_u64_serialized / -U64S)
+pub fn save_u64_serialized(values: &Vec<usize>, path: &str, compression: Compression) -> std::io::Result<()> {
+    let values: Vec<u64> = values.iter().map(|v| *v as u64).collect();
+    let mut output = Output::create(path, compression)?;
+    values.serialize(&mut Serializer::new(&mut output)).unwrap();
+    output.finish()
+}
+
+/// save u40 values serialized with rmp (readable with load_u40_serialized / -U40S)
+pub fn save_u40_serialized(values: &Vec<u40>, path: &str, compression: Compression) -> std::io::Result<()> {
+    let mut output = Output::create(path, compression)?;
+    values.serialize(&mut Serializer::new(&mut output)).unwrap();
+    output.finish()
+}
+
+/// save u40 values without any separator or other information (readable with load_u40_fit / -U40)
+pub fn save_u40_fit(values: &Vec<u40>, path: &str, compression: Compression) -> std::io::Result<()> {
+    let mut output = Output::create(path, compression)?;
+    for value in values {
+        let value = u64::from(*value);
+        output.write_all(&value.to_le_bytes()[0..5])?;
+    }
+    output.finish()
+}
+
+/// save u40 values with length of vector at start (readable with load_u40_tim / -U40T)
+pub fn save_u40_tim(values: &Vec<u40>, path: &str, compression: Compression) -> std::io::Result<()> {
+    let mut output = Output::create(path, compression)?;
+    output.write_all(&values.len().to_le_bytes())?;
+    for value in values {
+        let value = u64::from(*value);
+        output.write_all(&value.to_le_bytes()[0..std::mem::size_of::<u40>()])?;
     }
+    output.finish()
+}
+
+/// save u64 values with length of vector at start (readable with load_u64_tim / -U64T)
+pub fn save_u64_tim(values: &Vec<usize>, path: &str, compression: Compression) -> std::io::Result<()> {
+    let mut output = Output::create(path, compression)?;
+    output.write_all(&values.len().to_le_bytes())?;
+    for value in values {
+        output.write_all(&value.to_le_bytes())?;
+    }
+    output.finish()
 }
 
 // load usize values serialized with this module
 pub fn load(path: &str) -> std::io::Result<Vec<usize>> {
-    let input = BufReader::new(File::open(path).unwrap());
-    let mut deserializer = Deserializer::new(input);
+    let mut deserializer = Deserializer::new(open_input(path)?.0);
     let values: Vec<usize> = Deserialize::deserialize(&mut deserializer).unwrap();
     Ok(values)
 }
 
 // load u64 values serialized with this module
 pub fn load_u64_serialized(path: &str) -> std::io::Result<Vec<usize>> {
-    let input = BufReader::new(File::open(path).unwrap());
-    let mut deserializer = Deserializer::new(input);
+    let mut deserializer = Deserializer::new(open_input(path)?.0);
     let values: Vec<u64> = Deserialize::deserialize(&mut deserializer).unwrap();
     dbg!(values.len());
     Ok(values.into_iter().map(|v| v as usize).collect())
@@ -111,88 +357,163 @@ pub fn load_u64_serialized(path: &str) -> std::io::Result<Vec<usize>> {
 
 // load u40 values serialized with this module
 pub fn load_u40_serialized(path: &str) -> std::io::Result<Vec<u40>> {
-    let input = BufReader::new(File::open(path).unwrap());
-    let mut deserializer = Deserializer::new(input);
+    let mut deserializer = Deserializer::new(open_input(path)?.0);
     let values: Vec<u40> = Deserialize::deserialize(&mut deserializer).unwrap();
     Ok(values)
 }
 
-// load u40 values without any separator or other information
-pub fn load_u40_fit(path: &str) -> std::io::Result<Vec<u40>> {
-    let mut input = BufReader::new(File::open(path).unwrap());
-    let number_of_values = std::fs::metadata(path)?.len() as usize / 5;
-    let mut values: Vec<u40> = vec![u40::from(0); number_of_values];
-    let mut i = 0;
-    loop {
-        let mut buffer = Vec::new();
-        // read at most 5 bytes
-        input.by_ref().take(5).read_to_end(&mut buffer)?;
-        if buffer.len() < 5 {
-            if buffer.len() > 0 {
-                println!("Last Buffer: {:?}", buffer);
+/// number of bytes that are read at once by the chunked readers
+const READ_CHUNK_SIZE: usize = 1 << 20;
+
+/// reads little endian numbers of a fixed byte width chunk wise from a file
+/// can be used as iterator, so large files can be processed without loading them completely
+/// a read error is returned as item and ends the iteration
+/// if the number of values is known, an input with less or more values is an error too
+pub struct FixedWidthReader<R: Read> {
+    input: R,
+    buffer: Vec<u8>,
+    //position of next unread byte in buffer
+    pos: usize,
+    //number of valid bytes in buffer
+    filled: usize,
+    //bytes per number
+    width: usize,
+    //numbers that are still expected, None if input should be read until its end
+    remaining: Option<usize>,
+    //true after the last item (value, error or end) has been returned
+    finished: bool,
+}
+
+impl<R: Read> FixedWidthReader<R> {
+    /// width = number of bytes per value (at most 8)
+    /// number_of_values = number of values that should be read, None if unknown (e.g. compressed input)
+    pub fn new(input: R, width: usize, number_of_values: Option<usize>) -> FixedWidthReader<R> {
+        debug_assert!(width > 0 && width <= 8);
+        FixedWidthReader { input, buffer: vec![0; READ_CHUNK_SIZE], pos: 0, filled: 0, width, remaining: number_of_values, finished: false }
+    }
+
+    /// moves unread bytes to the front of the buffer and fills the rest
+    fn refill(&mut self) -> std::io::Result<()> {
+        let unread = self.filled - self.pos;
+        self.buffer.copy_within(self.pos..self.filled, 0);
+        self.pos = 0;
+        self.filled = unread;
+        while self.filled < self.buffer.len() {
+            let read = self.input.read(&mut self.buffer[self.filled..])?;
+            if read == 0 {
+                break;
             }
-            break;
+            self.filled += read;
         }
-        let u40: u64 = buffer[0] as u64 | ((buffer[1] as u64) << 8) | ((buffer[2] as u64) << 16) | ((buffer[3] as u64) << 24) | ((buffer[4] as u64) << 32);
-        values[i] = u40::from(u40);
-        debug_assert!(values.len() < 2 || values[values.len() - 2] < values[values.len() - 1]);
-        i += 1;
+        Ok(())
     }
-    debug_assert!(i == number_of_values);
-    Ok(values)
-}
 
-/// load u40 values with length of vector at start
-pub fn load_u40_tim(path: &str) -> std::io::Result<Vec<u40>> {
-    let mut input = BufReader::new(File::open(path).unwrap());
-    let mut lenv = Vec::new();
-    std::io::Read::by_ref(&mut input).take(std::mem::size_of::<usize>() as u64).read_to_end(&mut lenv)?;
-    let mut len: [u8; std::mem::size_of::<usize>()] = [0; std::mem::size_of::<usize>()];
-    for (i, b) in lenv.iter().enumerate() {
-        len[i] = *b;
+    /// true if there are bytes left after the expected values
+    fn has_more_input(&mut self) -> std::io::Result<bool> {
+        if self.filled == self.pos {
+            self.refill()?;
+        }
+        Ok(self.filled > self.pos)
     }
-    let len: usize = usize::from_le_bytes(len);
+}
 
-    assert!(len == (std::fs::metadata(path)?.len() as usize - std::mem::size_of::<usize>()) / std::mem::size_of::<u40>());
+impl<R: Read> Iterator for FixedWidthReader<R> {
+    type Item = std::io::Result<u64>;
 
-    let mut values: Vec<u40> = Vec::with_capacity(len);
-    while values.len() != len {
-        let mut buffer = Vec::with_capacity(std::mem::size_of::<u40>());
-        std::io::Read::by_ref(&mut input).take(std::mem::size_of::<u40>() as u64).read_to_end(&mut buffer)?;
-        let mut next_value: u64 = 0;
-        for i in 0..buffer.len() {
-            next_value |= (buffer[i] as u64) << (8 * i);
+    fn next(&mut self) -> Option<std::io::Result<u64>> {
+        if self.finished {
+            return None;
+        }
+        if self.remaining == Some(0) {
+            self.finished = true;
+            return match self.has_more_input() {
+                Ok(false) => None,
+                Ok(true) => Some(Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Input contains more values than declared"))),
+                Err(error) => Some(Err(error)),
+            };
+        }
+        if self.filled - self.pos < self.width {
+            if let Err(error) = self.refill() {
+                self.finished = true;
+                return Some(Err(error));
+            }
+            if self.filled - self.pos < self.width {
+                self.finished = true;
+                if let Some(remaining) = self.remaining {
+                    return Some(Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, format!("Input ends {} values before the declared number of values", remaining))));
+                }
+                if self.filled > self.pos {
+                    println!("Last Buffer: {:?}", &self.buffer[self.pos..self.filled]);
+                }
+                return None;
+            }
         }
+        let mut value: u64 = 0;
+        for i in 0..self.width {
+            value |= (self.buffer[self.pos + i] as u64) << (8 * i);
+        }
+        self.pos += self.width;
+        if let Some(remaining) = self.remaining.as_mut() {
+            *remaining -= 1;
+        }
+        Some(Ok(value))
+    }
 
-        values.push(u40::from(next_value));
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.remaining {
+            Some(remaining) => (remaining, Some(remaining)),
+            None => (0, None),
+        }
     }
-    Ok(values)
 }
 
-
-/// load u64 values with length of vector at start
-pub fn load_u64_tim(path: &str) -> std::io::Result<Vec<usize>> {
-    let mut input = BufReader::new(File::open(path).unwrap());
-    let mut lenv = Vec::new();
-    std::io::Read::by_ref(&mut input).take(std::mem::size_of::<usize>() as u64).read_to_end(&mut lenv)?;
+/// reads the number of values, that is stored in front of U40T and U64T files
+fn read_tim_header(input: &mut dyn Read) -> std::io::Result<usize> {
     let mut len: [u8; std::mem::size_of::<usize>()] = [0; std::mem::size_of::<usize>()];
-    for (i, b) in lenv.iter().enumerate() {
-        len[i] = *b;
-    }
-    let len: usize = usize::from_le_bytes(len);
+    input.read_exact(&mut len)?;
+    Ok(usize::from_le_bytes(len))
+}
 
-    assert!(len == (std::fs::metadata(path)?.len() as usize - std::mem::size_of::<usize>()) / std::mem::size_of::<usize>());
+/// stream u40 values without any separator or other information
+pub fn stream_u40_fit(path: &str) -> std::io::Result<impl Iterator<Item=std::io::Result<u40>>> {
+    let (input, compression) = open_input(path)?;
+    let number_of_values = if compression == Compression::None {
+        Some(std::fs::metadata(path)?.len() as usize / 5)
+    } else {
+        None
+    };
+    Ok(FixedWidthReader::new(input, 5, number_of_values).map(|v| v.map(u40::from)))
+}
 
-    let mut values: Vec<usize> = Vec::with_capacity(len);
-    while values.len() != len {
-        let mut buffer = Vec::with_capacity(std::mem::size_of::<usize>());
-        std::io::Read::by_ref(&mut input).take(std::mem::size_of::<usize>() as u64).read_to_end(&mut buffer)?;
-        let mut next_value: usize = 0;
-        for i in 0..buffer.len() {
-            next_value |= (buffer[i] as usize) << (8 * i);
-        }
+/// stream u40 values with length of vector at start
+pub fn stream_u40_tim(path: &str) -> std::io::Result<impl Iterator<Item=std::io::Result<u40>>> {
+    let (mut input, _) = open_input(path)?;
+    //the reader checks, that the input contains exactly len values
+    let len = read_tim_header(&mut input)?;
+    Ok(FixedWidthReader::new(input, std::mem::size_of::<u40>(), Some(len)).map(|v| v.map(u40::from)))
+}
 
-        values.push(next_value);
-    }
+/// stream u64 values with length of vector at start
+pub fn stream_u64_tim(path: &str) -> std::io::Result<impl Iterator<Item=std::io::Result<usize>>> {
+    let (mut input, _) = open_input(path)?;
+    //the reader checks, that the input contains exactly len values
+    let len = read_tim_header(&mut input)?;
+    Ok(FixedWidthReader::new(input, std::mem::size_of::<usize>(), Some(len)).map(|v| v.map(|v| v as usize)))
+}
+
+// load u40 values without any separator or other information
+pub fn load_u40_fit(path: &str) -> std::io::Result<Vec<u40>> {
+    let values: Vec<u40> = stream_u40_fit(path)?.collect::<std::io::Result<_>>()?;
+    debug_assert!(values.windows(2).all(|w| w[0] <= w[1]));
     Ok(values)
-}
\ No newline at end of file
+}
+
+/// load u40 values with length of vector at start
+pub fn load_u40_tim(path: &str) -> std::io::Result<Vec<u40>> {
+    stream_u40_tim(path)?.collect()
+}
+
+/// load u64 values with length of vector at start
+pub fn load_u64_tim(path: &str) -> std::io::Result<Vec<usize>> {
+    stream_u64_tim(path)?.collect()
+}
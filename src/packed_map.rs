@@ -0,0 +1,123 @@
+/// this module contains a static hash table for the lss levels of the tries
+/// keys and values are stored as packed 40 bit integers without any padding or control bytes (10 bytes per slot)
+/// collisions are resolved with linear probing
+
+use uint::u40;
+use level_map::{LevelMap, LevelMapConfig};
+
+pub type DataType = u40;
+
+/// load factor, if none is given
+pub const DEFAULT_LOAD_FACTOR: f64 = 0.75;
+/// 2^64 / golden ratio, spreads consecutive keys (like the prefixes of one level) evenly over the table
+const HASH_MULTIPLIER: u64 = 0x9E37_79B9_7F4A_7C15;
+/// marks empty slots, the key with the same value (leaf level 0) is stored outside of the slots
+const EMPTY: u64 = 0xFF_FFFF_FFFF;
+
+/// linear probing hash table from u40 to u40
+pub struct PackedU40Map {
+    // (key, value), key is EMPTY if slot is not used
+    slots: Vec<(DataType, DataType)>,
+    // number of bits of slot index
+    bits: u32,
+    // value of the key EMPTY, if it is stored
+    empty_key_value: Option<DataType>,
+    // number of stored keys
+    len: usize,
+}
+
+impl PackedU40Map {
+    /// keys must be unique, values[i] belongs to keys[i]
+    /// load_factor = maximal ratio of used slots (between 0 and 1)
+    pub fn with_load_factor(keys: Vec<DataType>, values: Vec<DataType>, load_factor: f64) -> PackedU40Map {
+        debug_assert!(keys.len() == values.len());
+        assert!(load_factor > 0. && load_factor < 1., "load factor has to be between 0 and 1");
+        let capacity = PackedU40Map::capacity_for(keys.len(), load_factor);
+        let mut map = PackedU40Map { slots: vec![(DataType::from(EMPTY), DataType::from(EMPTY)); capacity], bits: capacity.trailing_zeros(), empty_key_value: None, len: 0 };
+        for (key, value) in keys.into_iter().zip(values.into_iter()) {
+            map.insert(key, value);
+        }
+        map
+    }
+
+    /// number of slots of a table with len keys
+    fn capacity_for(len: usize, load_factor: f64) -> usize {
+        ((len as f64 / load_factor).ceil() as usize + 1).next_power_of_two()
+    }
+
+    /// table can't grow, so load factor given at construction must not be exceeded
+    fn insert(&mut self, key: DataType, value: DataType) {
+        if u64::from(key) == EMPTY {
+            if self.empty_key_value.is_none() {
+                self.len += 1;
+            }
+            self.empty_key_value = Some(value);
+            return;
+        }
+        debug_assert!(self.len < self.slots.len());
+        let mask = self.slots.len() - 1;
+        let mut slot = self.home_slot(key);
+        loop {
+            let slot_key = self.slots[slot].0;
+            if u64::from(slot_key) == EMPTY {
+                self.slots[slot] = (key, value);
+                self.len += 1;
+                return;
+            }
+            if slot_key == key {
+                self.slots[slot].1 = value;
+                return;
+            }
+            slot = (slot + 1) & mask;
+        }
+    }
+
+    /// multiplicative (fibonacci) hashing, the highest bits of the product are used
+    #[inline]
+    fn home_slot(&self, key: DataType) -> usize {
+        if self.bits == 0 {
+            return 0;
+        }
+        (u64::from(key).wrapping_mul(HASH_MULTIPLIER) >> (64 - self.bits)) as usize
+    }
+
+    /// number of slots
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+}
+
+impl LevelMap<DataType, DataType> for PackedU40Map {
+    fn from_sorted_pairs(keys: Vec<DataType>, values: Vec<DataType>) -> Self {
+        PackedU40Map::with_load_factor(keys, values, DEFAULT_LOAD_FACTOR)
+    }
+    fn from_sorted_pairs_with_config(keys: Vec<DataType>, values: Vec<DataType>, config: &LevelMapConfig) -> Self {
+        PackedU40Map::with_load_factor(keys, values, config.load_factor)
+    }
+    #[inline]
+    fn get(&self, key: &DataType) -> Option<&DataType> {
+        let mask = self.slots.len() - 1;
+        let mut slot = self.home_slot(*key);
+        unsafe {
+            loop {
+                let entry = self.slots.get_unchecked(slot);
+                if u64::from(entry.0) == EMPTY {
+                    return if u64::from(*key) == EMPTY { self.empty_key_value.as_ref() } else { None };
+                }
+                if entry.0 == *key {
+                    return Some(&entry.1);
+                }
+                slot = (slot + 1) & mask;
+            }
+        }
+    }
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn heap_size(&self) -> usize {
+        self.slots.capacity() * std::mem::size_of::<(DataType, DataType)>()
+    }
+    fn estimate_heap_size(len: usize, config: &LevelMapConfig) -> usize {
+        PackedU40Map::capacity_for(len, config.load_factor) * std::mem::size_of::<(DataType, DataType)>()
+    }
+}
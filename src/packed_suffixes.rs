@@ -0,0 +1,230 @@
+/// this module contains the leaf buckets of the split tries with a suffix width that matches the leaf level
+/// suffixes are stored bit packed in words, so a leaf level of 12 needs 12 bits per element and a leaf level of 24 no more than 24
+/// with a width of 8, 16 or 32 bits the words are read as a plain suffix array, so the simd search can be used
+
+use std::mem::size_of;
+use heap_size::HeapSize;
+use simd_search;
+
+const WORD_LEN: usize = 64;
+
+/// sorted suffixes of one leaf
+pub struct PackedSuffixes {
+    words: Vec<u64>,
+    len: u32,
+    width: u8,
+}
+
+impl PackedSuffixes {
+    /// bucket with one suffix of width bits
+    pub fn from_suffix(width: usize, suffix: u64) -> PackedSuffixes {
+        assert!(width < WORD_LEN, "Suffix width has to be smaller than {}", WORD_LEN);
+        let mut suffixes = PackedSuffixes { words: Vec::new(), len: 0, width: width as u8 };
+        suffixes.push(suffix);
+        suffixes
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    #[inline]
+    fn mask(&self) -> u64 {
+        (1u64 << self.width) - 1
+    }
+
+    /// suffix at position pos
+    #[inline]
+    pub fn get(&self, pos: usize) -> u64 {
+        debug_assert!(pos < self.len());
+        let width = self.width as usize;
+        if width == 0 {
+            return 0;
+        }
+        let offset = pos * width;
+        let shift = offset % WORD_LEN;
+        unsafe {
+            let word = *self.words.get_unchecked(offset / WORD_LEN) >> shift;
+            if shift + width > WORD_LEN {
+                (word | *self.words.get_unchecked(offset / WORD_LEN + 1) << (WORD_LEN - shift)) & self.mask()
+            } else {
+                word & self.mask()
+            }
+        }
+    }
+
+    fn set(&mut self, pos: usize, suffix: u64) {
+        let width = self.width as usize;
+        if width == 0 {
+            return;
+        }
+        let suffix = suffix & self.mask();
+        let offset = pos * width;
+        let shift = offset % WORD_LEN;
+        let word = offset / WORD_LEN;
+        self.words[word] = self.words[word] & !(self.mask() << shift) | suffix << shift;
+        if shift + width > WORD_LEN {
+            let high_mask = self.mask() >> (WORD_LEN - shift);
+            self.words[word + 1] = self.words[word + 1] & !high_mask | suffix >> (WORD_LEN - shift);
+        }
+    }
+
+    /// ensures the words can hold len suffixes
+    fn reserve_for(&mut self, len: usize) {
+        let words = (len * self.width as usize + WORD_LEN - 1) / WORD_LEN;
+        if words > self.words.len() {
+            self.words.resize(words, 0);
+        }
+    }
+
+    ///suffix must not be smaller than the last one
+    pub fn push(&mut self, suffix: u64) {
+        let len = self.len();
+        self.reserve_for(len + 1);
+        self.set(len, suffix);
+        self.len += 1;
+    }
+
+    pub fn insert(&mut self, pos: usize, suffix: u64) {
+        let len = self.len();
+        debug_assert!(pos <= len);
+        self.reserve_for(len + 1);
+        self.shift_right(pos);
+        self.set(pos, suffix);
+        self.len += 1;
+    }
+
+    pub fn remove(&mut self, pos: usize) {
+        debug_assert!(pos < self.len());
+        self.shift_left(pos);
+        self.len -= 1;
+        let words = (self.len() * self.width as usize + WORD_LEN - 1) / WORD_LEN;
+        self.words.truncate(words);
+    }
+
+    /// moves the suffixes from pos to the end one position to the right, the words must have space for one more suffix
+    fn shift_right(&mut self, pos: usize) {
+        let width = self.width as usize;
+        let len = self.len();
+        #[cfg(target_endian = "little")]
+        {
+            if width == 8 {
+                let suffixes = unsafe { std::slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u8, len + 1) };
+                suffixes.copy_within(pos..len, pos + 1);
+                return;
+            }
+            if width == 16 {
+                let suffixes = unsafe { std::slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u16, len + 1) };
+                suffixes.copy_within(pos..len, pos + 1);
+                return;
+            }
+            if width == 32 {
+                let suffixes = unsafe { std::slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u32, len + 1) };
+                suffixes.copy_within(pos..len, pos + 1);
+                return;
+            }
+        }
+        if width == 0 || pos == len {
+            return;
+        }
+        let first = pos * width / WORD_LEN;
+        //bits before pos stay where they are
+        let low = (1u64 << (pos * width % WORD_LEN)) - 1;
+        //beginning with the last word, each word takes the highest bits of the word before
+        for word in (first + 1..self.words.len()).rev() {
+            let before = if word - 1 == first { self.words[first] & !low } else { self.words[word - 1] };
+            self.words[word] = self.words[word] << width | before >> (WORD_LEN - width);
+        }
+        self.words[first] = self.words[first] & low | (self.words[first] & !low) << width;
+    }
+
+    /// overwrites the suffix at pos by moving the following suffixes one position to the left, the last position is cleared
+    fn shift_left(&mut self, pos: usize) {
+        let width = self.width as usize;
+        let len = self.len();
+        #[cfg(target_endian = "little")]
+        {
+            if width == 8 {
+                let suffixes = unsafe { std::slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u8, len) };
+                suffixes.copy_within(pos + 1..len, pos);
+                suffixes[len - 1] = 0;
+                return;
+            }
+            if width == 16 {
+                let suffixes = unsafe { std::slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u16, len) };
+                suffixes.copy_within(pos + 1..len, pos);
+                suffixes[len - 1] = 0;
+                return;
+            }
+            if width == 32 {
+                let suffixes = unsafe { std::slice::from_raw_parts_mut(self.words.as_mut_ptr() as *mut u32, len) };
+                suffixes.copy_within(pos + 1..len, pos);
+                suffixes[len - 1] = 0;
+                return;
+            }
+        }
+        if width == 0 {
+            return;
+        }
+        let first = pos * width / WORD_LEN;
+        let low = (1u64 << (pos * width % WORD_LEN)) - 1;
+        let last = self.words.len() - 1;
+        //each word takes the lowest bits of the word behind, the bits behind the last suffix are 0
+        for word in first..last + 1 {
+            let behind = if word < last { self.words[word + 1] << (WORD_LEN - width) } else { 0 };
+            let shifted = self.words[word] >> width | behind;
+            self.words[word] = if word == first { self.words[word] & low | shifted & !low } else { shifted };
+        }
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.words.shrink_to_fit();
+    }
+
+    /// first position, whose suffix is not smaller than suffix
+    #[inline]
+    pub fn lower_bound(&self, suffix: u64) -> usize {
+        #[cfg(target_endian = "little")]
+        {
+            //the packed words are a plain array of u8, u16 or u32 values
+            if self.width == 8 {
+                let suffixes = unsafe { std::slice::from_raw_parts(self.words.as_ptr() as *const u8, self.len()) };
+                return simd_search::lower_bound_u8(suffixes, suffix as u8);
+            }
+            if self.width == 16 {
+                let suffixes = unsafe { std::slice::from_raw_parts(self.words.as_ptr() as *const u16, self.len()) };
+                return simd_search::lower_bound_u16(suffixes, suffix as u16);
+            }
+            if self.width == 32 {
+                let suffixes = unsafe { std::slice::from_raw_parts(self.words.as_ptr() as *const u32, self.len()) };
+                return simd_search::lower_bound_u32(suffixes, suffix as u32);
+            }
+        }
+        let (mut left, mut right) = (0, self.len());
+        while left < right {
+            let middle = (left + right) / 2;
+            if self.get(middle) < suffix {
+                left = middle + 1;
+            } else {
+                right = middle;
+            }
+        }
+        left
+    }
+
+    /// same result as binary_search on a slice
+    pub fn binary_search(&self, suffix: u64) -> Result<usize, usize> {
+        let pos = self.lower_bound(suffix);
+        if pos < self.len() && self.get(pos) == suffix {
+            Ok(pos)
+        } else {
+            Err(pos)
+        }
+    }
+}
+
+impl HeapSize for PackedSuffixes {
+    fn heap_size(&self) -> usize {
+        self.words.capacity() * size_of::<u64>()
+    }
+}
@@ -0,0 +1,240 @@
+/// this module contains the predecessor search inside the leaf buckets of the split tries and the nodes of the b-tree
+/// large buckets are narrowed with binary search, the rest is counted with sse2/avx2 comparisons
+/// the instruction set is detected once at runtime, other architectures use a scalar count
+
+#[cfg(target_arch = "x86")]
+use std::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// ranges up to this length are counted instead of halved
+const COUNT_THRESHOLD: usize = 128;
+
+/// first position in the sorted elements, that is not smaller than query
+pub fn lower_bound_u8(elements: &[u8], query: u8) -> usize {
+    let (left, right) = narrow(elements, query);
+    left + count_less_u8(&elements[left..right], query)
+}
+
+/// first position in the sorted elements, that is not smaller than query
+pub fn lower_bound_u16(elements: &[u16], query: u16) -> usize {
+    let (left, right) = narrow(elements, query);
+    left + count_less_u16(&elements[left..right], query)
+}
+
+/// first position in the sorted elements, that is not smaller than query
+pub fn lower_bound_u32(elements: &[u32], query: u32) -> usize {
+    let (left, right) = narrow(elements, query);
+    left + count_less_u32(&elements[left..right], query)
+}
+
+pub fn contains_u16(elements: &[u16], query: u16) -> bool {
+    let pos = lower_bound_u16(elements, query);
+    pos < elements.len() && elements[pos] == query
+}
+
+pub fn contains_u32(elements: &[u32], query: u32) -> bool {
+    let pos = lower_bound_u32(elements, query);
+    pos < elements.len() && elements[pos] == query
+}
+
+/// binary search until at most COUNT_THRESHOLD elements are left
+/// elements before the returned range are smaller, elements behind are not smaller than query
+#[inline]
+fn narrow<T: PartialOrd>(elements: &[T], query: T) -> (usize, usize) {
+    let (mut left, mut right) = (0, elements.len());
+    while right - left > COUNT_THRESHOLD {
+        let middle = (left + right) / 2;
+        if unsafe { elements.get_unchecked(middle) } < &query {
+            left = middle + 1;
+        } else {
+            right = middle;
+        }
+    }
+    (left, right)
+}
+
+#[inline]
+fn count_less_scalar<T: PartialOrd>(elements: &[T], query: T) -> usize {
+    let mut count = 0;
+    for element in elements {
+        count += (*element < query) as usize;
+    }
+    count
+}
+
+/// instruction set of the counting functions
+#[derive(Clone, Copy)]
+#[cfg_attr(not(any(target_arch = "x86", target_arch = "x86_64")), allow(dead_code))]
+enum InstructionSet {
+    Scalar = 1,
+    Sse2 = 2,
+    Avx2 = 3,
+}
+
+/// detected instruction set, 0 until the first search
+static INSTRUCTION_SET: AtomicUsize = AtomicUsize::new(0);
+
+/// the detection runs only once, later searches load the stored result
+#[inline]
+fn instruction_set() -> InstructionSet {
+    match INSTRUCTION_SET.load(Ordering::Relaxed) {
+        1 => InstructionSet::Scalar,
+        2 => InstructionSet::Sse2,
+        3 => InstructionSet::Avx2,
+        _ => {
+            let detected = detect_instruction_set();
+            INSTRUCTION_SET.store(detected as usize, Ordering::Relaxed);
+            detected
+        }
+    }
+}
+
+#[cold]
+fn detect_instruction_set() -> InstructionSet {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        if is_x86_feature_detected!("avx2") {
+            return InstructionSet::Avx2;
+        }
+        if is_x86_feature_detected!("sse2") {
+            return InstructionSet::Sse2;
+        }
+    }
+    InstructionSet::Scalar
+}
+
+fn count_less_u8(elements: &[u8], query: u8) -> usize {
+    match instruction_set() {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        InstructionSet::Avx2 => unsafe { count_less_u8_avx2(elements, query) },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        InstructionSet::Sse2 => unsafe { count_less_u8_sse2(elements, query) },
+        _ => count_less_scalar(elements, query),
+    }
+}
+
+fn count_less_u16(elements: &[u16], query: u16) -> usize {
+    match instruction_set() {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        InstructionSet::Avx2 => unsafe { count_less_u16_avx2(elements, query) },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        InstructionSet::Sse2 => unsafe { count_less_u16_sse2(elements, query) },
+        _ => count_less_scalar(elements, query),
+    }
+}
+
+fn count_less_u32(elements: &[u32], query: u32) -> usize {
+    match instruction_set() {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        InstructionSet::Avx2 => unsafe { count_less_u32_avx2(elements, query) },
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        InstructionSet::Sse2 => unsafe { count_less_u32_sse2(elements, query) },
+        _ => count_less_scalar(elements, query),
+    }
+}
+
+// there are only signed comparisons, flipping the sign bit keeps the unsigned order
+
+/// 32 bytes per comparison, movemask has 1 bit per byte
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn count_less_u8_avx2(elements: &[u8], query: u8) -> usize {
+    let sign = _mm256_set1_epi8(i8::min_value());
+    let query_vector = _mm256_xor_si256(_mm256_set1_epi8(query as i8), sign);
+    let mut count = 0;
+    let chunks = elements.chunks_exact(32);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        let values = _mm256_xor_si256(_mm256_loadu_si256(chunk.as_ptr() as *const __m256i), sign);
+        let less = _mm256_cmpgt_epi8(query_vector, values);
+        count += (_mm256_movemask_epi8(less) as u32).count_ones() as usize;
+    }
+    count + count_less_scalar(rest, query)
+}
+
+/// 16 bytes per comparison, movemask has 1 bit per byte
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+unsafe fn count_less_u8_sse2(elements: &[u8], query: u8) -> usize {
+    let sign = _mm_set1_epi8(i8::min_value());
+    let query_vector = _mm_xor_si128(_mm_set1_epi8(query as i8), sign);
+    let mut count = 0;
+    let chunks = elements.chunks_exact(16);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        let values = _mm_xor_si128(_mm_loadu_si128(chunk.as_ptr() as *const __m128i), sign);
+        let less = _mm_cmpgt_epi8(query_vector, values);
+        count += (_mm_movemask_epi8(less) as u32).count_ones() as usize;
+    }
+    count + count_less_scalar(rest, query)
+}
+
+/// 16 suffixes per comparison, movemask has 2 bits per suffix
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn count_less_u16_avx2(elements: &[u16], query: u16) -> usize {
+    let sign = _mm256_set1_epi16(i16::min_value());
+    let query_vector = _mm256_xor_si256(_mm256_set1_epi16(query as i16), sign);
+    let mut count = 0;
+    let chunks = elements.chunks_exact(16);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        let values = _mm256_xor_si256(_mm256_loadu_si256(chunk.as_ptr() as *const __m256i), sign);
+        let less = _mm256_cmpgt_epi16(query_vector, values);
+        count += (_mm256_movemask_epi8(less) as u32).count_ones() as usize / 2;
+    }
+    count + count_less_scalar(rest, query)
+}
+
+/// 8 suffixes per comparison, movemask has 2 bits per suffix
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+unsafe fn count_less_u16_sse2(elements: &[u16], query: u16) -> usize {
+    let sign = _mm_set1_epi16(i16::min_value());
+    let query_vector = _mm_xor_si128(_mm_set1_epi16(query as i16), sign);
+    let mut count = 0;
+    let chunks = elements.chunks_exact(8);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        let values = _mm_xor_si128(_mm_loadu_si128(chunk.as_ptr() as *const __m128i), sign);
+        let less = _mm_cmpgt_epi16(query_vector, values);
+        count += (_mm_movemask_epi8(less) as u32).count_ones() as usize / 2;
+    }
+    count + count_less_scalar(rest, query)
+}
+
+/// 8 suffixes per comparison, movemask has 4 bits per suffix
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn count_less_u32_avx2(elements: &[u32], query: u32) -> usize {
+    let sign = _mm256_set1_epi32(i32::min_value());
+    let query_vector = _mm256_xor_si256(_mm256_set1_epi32(query as i32), sign);
+    let mut count = 0;
+    let chunks = elements.chunks_exact(8);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        let values = _mm256_xor_si256(_mm256_loadu_si256(chunk.as_ptr() as *const __m256i), sign);
+        let less = _mm256_cmpgt_epi32(query_vector, values);
+        count += (_mm256_movemask_epi8(less) as u32).count_ones() as usize / 4;
+    }
+    count + count_less_scalar(rest, query)
+}
+
+/// 4 suffixes per comparison, movemask has 4 bits per suffix
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "sse2")]
+unsafe fn count_less_u32_sse2(elements: &[u32], query: u32) -> usize {
+    let sign = _mm_set1_epi32(i32::min_value());
+    let query_vector = _mm_xor_si128(_mm_set1_epi32(query as i32), sign);
+    let mut count = 0;
+    let chunks = elements.chunks_exact(4);
+    let rest = chunks.remainder();
+    for chunk in chunks {
+        let values = _mm_xor_si128(_mm_loadu_si128(chunk.as_ptr() as *const __m128i), sign);
+        let less = _mm_cmpgt_epi32(query_vector, values);
+        count += (_mm_movemask_epi8(less) as u32).count_ones() as usize / 4;
+    }
+    count + count_less_scalar(rest, query)
+}
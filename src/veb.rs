@@ -0,0 +1,345 @@
+extern crate fnv;
+
+/// this module contains a van Emde Boas tree with hashed clusters
+/// only non empty clusters are stored in a hash map, so the tree needs O(n) space instead of O(u)
+/// every node stores its minimum outside of the clusters, universes of up to 64 values are stored as bitmap
+
+use std::mem::size_of;
+use self::fnv::FnvHashMap;
+use heap_size::HeapSize;
+use uint::u40;
+use predecessor_set::PredecessorSet;
+
+/// universes up to 2^LEAF_BITS are stored in one word
+const LEAF_BITS: usize = 6;
+
+enum Node {
+    Leaf(u64),
+    Inner(Box<Inner>),
+}
+
+struct Inner {
+    min: u64,
+    //max is stored in the clusters, unless it is the min
+    max: u64,
+    //high parts of the non empty clusters, None if there is no cluster
+    summary: Option<Node>,
+    clusters: FnvHashMap<u64, Node>,
+}
+
+/// (bits of the high part, bits of the low part)
+#[inline]
+fn split_bits(bits: usize) -> (usize, usize) {
+    (bits - bits / 2, bits / 2)
+}
+
+#[inline]
+fn high(x: u64, bits: usize) -> u64 {
+    x >> (bits / 2)
+}
+
+#[inline]
+fn low(x: u64, bits: usize) -> u64 {
+    x & ((1u64 << (bits / 2)) - 1)
+}
+
+#[inline]
+fn join(high: u64, low: u64, bits: usize) -> u64 {
+    high << (bits / 2) | low
+}
+
+impl Node {
+    /// node with the single element x in a universe of 2^bits
+    fn new(bits: usize, x: u64) -> Node {
+        if bits <= LEAF_BITS {
+            Node::Leaf(1u64 << x)
+        } else {
+            Node::Inner(Box::new(Inner { min: x, max: x, summary: None, clusters: FnvHashMap::default() }))
+        }
+    }
+
+    fn min(&self) -> u64 {
+        match self {
+            Node::Leaf(word) => word.trailing_zeros() as u64,
+            Node::Inner(inner) => inner.min,
+        }
+    }
+
+    fn max(&self) -> u64 {
+        match self {
+            Node::Leaf(word) => 63 - word.leading_zeros() as u64,
+            Node::Inner(inner) => inner.max,
+        }
+    }
+
+    fn contains(&self, bits: usize, x: u64) -> bool {
+        match self {
+            Node::Leaf(word) => word & (1u64 << x) != 0,
+            Node::Inner(inner) => {
+                x == inner.min || x == inner.max || match inner.clusters.get(&high(x, bits)) {
+                    Some(cluster) => cluster.contains(split_bits(bits).1, low(x, bits)),
+                    None => false,
+                }
+            }
+        }
+    }
+
+    ///x must not be in the node
+    fn insert(&mut self, bits: usize, mut x: u64) {
+        match self {
+            Node::Leaf(word) => *word |= 1u64 << x,
+            Node::Inner(inner) => {
+                if x < inner.min {
+                    //new min is not stored in the clusters, the old one is
+                    std::mem::swap(&mut x, &mut inner.min);
+                }
+                if x > inner.max {
+                    inner.max = x;
+                }
+                let (high_bits, low_bits) = split_bits(bits);
+                let h = high(x, bits);
+                if let Some(cluster) = inner.clusters.get_mut(&h) {
+                    cluster.insert(low_bits, low(x, bits));
+                    return;
+                }
+                inner.clusters.insert(h, Node::new(low_bits, low(x, bits)));
+                match inner.summary {
+                    Some(ref mut summary) => summary.insert(high_bits, h),
+                    None => inner.summary = Some(Node::new(high_bits, h)),
+                }
+            }
+        }
+    }
+
+    ///x must be in the node
+    ///returns true, if the node is empty now and has to be removed
+    fn delete(&mut self, bits: usize, mut x: u64) -> bool {
+        match self {
+            Node::Leaf(word) => {
+                *word &= !(1u64 << x);
+                *word == 0
+            }
+            Node::Inner(inner) => {
+                let (high_bits, low_bits) = split_bits(bits);
+                if inner.summary.is_none() {
+                    //min is the only element
+                    debug_assert!(x == inner.min && inner.min == inner.max);
+                    return true;
+                }
+                if x == inner.min {
+                    //smallest element of the clusters becomes the new min and is removed from its cluster
+                    let h = inner.summary.as_ref().unwrap().min();
+                    x = join(h, inner.clusters[&h].min(), bits);
+                    inner.min = x;
+                }
+                let h = high(x, bits);
+                if inner.clusters.get_mut(&h).unwrap().delete(low_bits, low(x, bits)) {
+                    inner.clusters.remove(&h);
+                    if inner.summary.as_mut().unwrap().delete(high_bits, h) {
+                        inner.summary = None;
+                    }
+                }
+                if x == inner.max {
+                    inner.max = match inner.summary {
+                        Some(ref summary) => {
+                            let h = summary.max();
+                            join(h, inner.clusters[&h].max(), bits)
+                        }
+                        None => inner.min,
+                    };
+                }
+                false
+            }
+        }
+    }
+
+    /// largest element smaller than x
+    fn predecessor(&self, bits: usize, x: u64) -> Option<u64> {
+        match self {
+            Node::Leaf(word) => {
+                let smaller = word & ((1u64 << x) - 1);
+                if smaller == 0 { None } else { Some(63 - smaller.leading_zeros() as u64) }
+            }
+            Node::Inner(inner) => {
+                if x <= inner.min {
+                    return None;
+                }
+                if x > inner.max {
+                    return Some(inner.max);
+                }
+                let (high_bits, low_bits) = split_bits(bits);
+                let h = high(x, bits);
+                if let Some(cluster) = inner.clusters.get(&h) {
+                    let l = low(x, bits);
+                    if cluster.min() < l {
+                        return Some(join(h, cluster.predecessor(low_bits, l).unwrap(), bits));
+                    }
+                }
+                //max of the previous cluster or min, which is not stored in the clusters
+                match inner.summary.as_ref().and_then(|summary| summary.predecessor(high_bits, h)) {
+                    Some(previous) => Some(join(previous, inner.clusters[&previous].max(), bits)),
+                    None => Some(inner.min),
+                }
+            }
+        }
+    }
+
+    /// smallest element greater than x
+    fn successor(&self, bits: usize, x: u64) -> Option<u64> {
+        match self {
+            Node::Leaf(word) => {
+                let greater = if x >= 63 { 0 } else { word & !((2u64 << x) - 1) };
+                if greater == 0 { None } else { Some(greater.trailing_zeros() as u64) }
+            }
+            Node::Inner(inner) => {
+                if x < inner.min {
+                    return Some(inner.min);
+                }
+                if x >= inner.max {
+                    return None;
+                }
+                let (high_bits, low_bits) = split_bits(bits);
+                let h = high(x, bits);
+                if let Some(cluster) = inner.clusters.get(&h) {
+                    let l = low(x, bits);
+                    if l < cluster.max() {
+                        return Some(join(h, cluster.successor(low_bits, l).unwrap(), bits));
+                    }
+                }
+                //max is greater than x and stored in a following cluster
+                let next = inner.summary.as_ref().unwrap().successor(high_bits, h).unwrap();
+                Some(join(next, inner.clusters[&next].min(), bits))
+            }
+        }
+    }
+}
+
+impl HeapSize for Node {
+    fn heap_size(&self) -> usize {
+        match self {
+            Node::Leaf(_) => 0,
+            Node::Inner(inner) => size_of::<Inner>() + inner.summary.as_ref().map_or(0, |summary| summary.heap_size()) + inner.clusters.heap_size(),
+        }
+    }
+}
+
+/// dynamic van Emde Boas tree for elements smaller than 2^bits
+pub struct VEB {
+    bits: usize,
+    root: Option<Node>,
+    len: usize,
+}
+
+impl VEB {
+    pub fn new(bits: usize) -> VEB {
+        assert!(bits > 0 && bits <= 64, "Bit length has to be between 1 and 64");
+        VEB { bits, root: None, len: 0 }
+    }
+
+    pub fn from_elements<I: Iterator<Item=u64>>(bits: usize, elements: I) -> VEB {
+        let mut veb = VEB::new(bits);
+        for element in elements {
+            veb.insert_u64(element);
+        }
+        veb
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn contains_u64(&self, x: u64) -> bool {
+        self.root.as_ref().map_or(false, |root| root.contains(self.bits, x))
+    }
+
+    pub fn insert_u64(&mut self, x: u64) {
+        assert!(self.bits == 64 || x >> self.bits == 0, "Element {} is too large for {} bits", x, self.bits);
+        if self.contains_u64(x) {
+            return;
+        }
+        match self.root {
+            Some(ref mut root) => root.insert(self.bits, x),
+            None => self.root = Some(Node::new(self.bits, x)),
+        }
+        self.len += 1;
+    }
+
+    pub fn delete_u64(&mut self, x: u64) {
+        if !self.contains_u64(x) {
+            return;
+        }
+        if self.root.as_mut().unwrap().delete(self.bits, x) {
+            self.root = None;
+        }
+        self.len -= 1;
+    }
+
+    pub fn predecessor_u64(&self, x: u64) -> Option<u64> {
+        self.root.as_ref()?.predecessor(self.bits, x)
+    }
+
+    pub fn successor_u64(&self, x: u64) -> Option<u64> {
+        self.root.as_ref()?.successor(self.bits, x)
+    }
+
+    pub fn minimum_u64(&self) -> Option<u64> {
+        self.root.as_ref().map(|root| root.min())
+    }
+
+    pub fn maximum_u64(&self) -> Option<u64> {
+        self.root.as_ref().map(|root| root.max())
+    }
+
+    ///bytes on the heap
+    pub fn heap_size(&self) -> usize {
+        self.root.as_ref().map_or(0, |root| root.heap_size())
+    }
+}
+
+impl PredecessorSet<u40> for VEB {
+    fn predecessor(&self, number: u40) -> Option<u40> {
+        self.predecessor_u64(u64::from(number)).map(|v| u40::from(v))
+    }
+    fn insert(&mut self, element: u40) {
+        self.insert_u64(u64::from(element))
+    }
+    fn delete(&mut self, element: u40) {
+        self.delete_u64(u64::from(element))
+    }
+    fn successor(&self, number: u40) -> Option<u40> {
+        self.successor_u64(u64::from(number)).map(|v| u40::from(v))
+    }
+    fn minimum(&self) -> Option<u40> {
+        self.minimum_u64().map(|v| u40::from(v))
+    }
+    fn maximum(&self) -> Option<u40> {
+        self.maximum_u64().map(|v| u40::from(v))
+    }
+    fn contains(&self, number: u40) -> bool {
+        self.contains_u64(u64::from(number))
+    }
+}
+
+impl PredecessorSet<usize> for VEB {
+    fn predecessor(&self, number: usize) -> Option<usize> {
+        self.predecessor_u64(number as u64).map(|v| v as usize)
+    }
+    fn insert(&mut self, element: usize) {
+        self.insert_u64(element as u64)
+    }
+    fn delete(&mut self, element: usize) {
+        self.delete_u64(element as u64)
+    }
+    fn successor(&self, number: usize) -> Option<usize> {
+        self.successor_u64(number as u64).map(|v| v as usize)
+    }
+    fn minimum(&self) -> Option<usize> {
+        self.minimum_u64().map(|v| v as usize)
+    }
+    fn maximum(&self) -> Option<usize> {
+        self.maximum_u64().map(|v| v as usize)
+    }
+    fn contains(&self, number: usize) -> bool {
+        self.contains_u64(number as u64)
+    }
+}
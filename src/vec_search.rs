@@ -1,6 +1,7 @@
 /// this module contains some search methods on vectors
 
 use uint::u40;
+use std::cell::Cell;
 use std::collections::BTreeSet;
 
 ///binary search predecessor
@@ -67,4 +68,158 @@ pub fn mixed_search_pred(element_list: &Vec<u40>, query: u40, bin_search_steps:
 ///search predecessor with BTree
 pub fn btree_search_pred(set: &BTreeSet<usize>, query: usize) -> Option<usize> {
     Some(*set.range(0..query).last()?)
-}
\ No newline at end of file
+}
+/// keys of the baselines below, they work on u40 and usize input
+pub trait SearchKey: Copy + Ord {
+    fn to_u64(self) -> u64;
+}
+
+impl SearchKey for u40 {
+    #[inline]
+    fn to_u64(self) -> u64 {
+        u64::from(self)
+    }
+}
+
+impl SearchKey for usize {
+    #[inline]
+    fn to_u64(self) -> u64 {
+        self as u64
+    }
+}
+
+/// predecessor from the first position, whose element is not smaller than query
+#[inline]
+fn pred_at<T: SearchKey>(element_list: &Vec<T>, pos: usize) -> Option<T> {
+    if pos > 0 {
+        unsafe {
+            Some(*element_list.get_unchecked(pos - 1))
+        }
+    } else {
+        None
+    }
+}
+
+/// position between l and r (both included), where query would be, if the elements between l and r are evenly distributed
+/// element_list[l] < query <= element_list[r] is required
+#[inline]
+fn interpolate<T: SearchKey>(element_list: &Vec<T>, query: T, l: usize, r: usize) -> usize {
+    unsafe {
+        let low = element_list.get_unchecked(l).to_u64();
+        let high = element_list.get_unchecked(r).to_u64();
+        let fraction = (query.to_u64() - low) as f64 / (high - low) as f64;
+        (l + (fraction * (r - l) as f64) as usize).min(r)
+    }
+}
+
+/// interpolation search predecessor
+/// ranges of up to 8 elements are searched linear
+pub fn interpolation_search_pred<T: SearchKey>(element_list: &Vec<T>, query: T) -> Option<T> {
+    if element_list.len() == 0 {
+        return None;
+    }
+    unsafe {
+        //elements before l are smaller and elements from r on are not smaller than query
+        let mut l = 0;
+        let mut r = element_list.len();
+        while r - l > 8 {
+            if *element_list.get_unchecked(l) >= query {
+                return pred_at(element_list, l);
+            }
+            if *element_list.get_unchecked(r - 1) < query {
+                return pred_at(element_list, r);
+            }
+            let m = interpolate(element_list, query, l, r - 1);
+            if *element_list.get_unchecked(m) < query {
+                l = m + 1;
+            } else {
+                r = m;
+            }
+        }
+        while l < r && element_list.get_unchecked(l) < &query {
+            l += 1;
+        }
+        pred_at(element_list, l)
+    }
+}
+
+/// interpolation-sequential search predecessor
+/// one interpolation over the whole list, from there linear search in the direction of query
+pub fn interpolation_sequential_search_pred<T: SearchKey>(element_list: &Vec<T>, query: T) -> Option<T> {
+    if element_list.len() == 0 {
+        return None;
+    }
+    unsafe {
+        let last = element_list.len() - 1;
+        if *element_list.get_unchecked(0) >= query {
+            return None;
+        }
+        if *element_list.get_unchecked(last) < query {
+            return Some(*element_list.get_unchecked(last));
+        }
+        let mut pos = interpolate(element_list, query, 0, last);
+        if *element_list.get_unchecked(pos) < query {
+            while *element_list.get_unchecked(pos + 1) < query {
+                pos += 1;
+            }
+            Some(*element_list.get_unchecked(pos))
+        } else {
+            while *element_list.get_unchecked(pos - 1) >= query {
+                pos -= 1;
+            }
+            Some(*element_list.get_unchecked(pos - 1))
+        }
+    }
+}
+
+/// exponential (galloping) search predecessor, beginning at the position of hint
+/// hint is set to the position of the result, so sorted queries are found in few steps
+pub fn exponential_search_pred<T: SearchKey>(element_list: &Vec<T>, query: T, hint: &Cell<usize>) -> Option<T> {
+    let len = element_list.len();
+    if len == 0 {
+        return None;
+    }
+    let start = hint.get().min(len - 1);
+    //bounds of the lower bound of query, l included, r excluded
+    let (l, r);
+    let mut bound = 1;
+    unsafe {
+        if *element_list.get_unchecked(start) < query {
+            while start + bound < len && *element_list.get_unchecked(start + bound) < query {
+                bound *= 2;
+            }
+            l = start + bound / 2 + 1;
+            r = (start + bound + 1).min(len);
+        } else {
+            while bound <= start && *element_list.get_unchecked(start - bound) >= query {
+                bound *= 2;
+            }
+            l = if bound <= start { start - bound + 1 } else { 0 };
+            r = start - bound / 2 + 1;
+        }
+    }
+    let pos = match element_list[l..r].binary_search(&query) {
+        Ok(pos) => pos + l,
+        Err(pos) => pos + l
+    };
+    hint.set(pos);
+    pred_at(element_list, pos)
+}
+
+/// binary search predecessor without branches in the loop (the compiler uses conditional moves)
+pub fn branchless_bin_search_pred<T: SearchKey>(element_list: &Vec<T>, query: T) -> Option<T> {
+    let mut len = element_list.len();
+    if len == 0 {
+        return None;
+    }
+    let mut base = 0;
+    unsafe {
+        while len > 1 {
+            let half = len / 2;
+            base = if *element_list.get_unchecked(base + half) < query { base + half } else { base };
+            len -= half;
+        }
+        let pos = base + (*element_list.get_unchecked(base) < query) as usize;
+        pred_at(element_list, pos)
+    }
+}
@@ -0,0 +1,276 @@
+extern crate fnv;
+
+/// this module contains a complete X-fast trie without the tricks of the Y-fast tries
+/// every level of the trie is stored in a hash map, nodes with one child point to the leaf next to the missing child
+/// the leaves are a doubly linked list, so predecessor and successor need one search for the lowest existing ancestor
+/// it is meant as reference for correctness and to measure the space, that leaf groups, lss_top and bucketing save
+
+use log::Log;
+use heap_size::{HeapSize, MemoryBreakdown, TrieMemory};
+use uint::u40;
+use self::fnv::FnvHashMap;
+use predecessor_set::PredecessorSet;
+use level_keys::{calc_path, is_left_child};
+
+pub type DataType = u40;
+
+const BIT_LENGTH: usize = 40;
+const LEFT: u8 = 1;
+const RIGHT: u8 = 2;
+
+struct TrieNode {
+    // LEFT | RIGHT
+    children: u8,
+    // without left child: smallest leaf of the right subtree, without right child: largest leaf of the left subtree
+    descendant: DataType,
+}
+
+impl HeapSize for TrieNode {}
+
+struct Leaf {
+    predecessor: Option<DataType>,
+    successor: Option<DataType>,
+}
+
+impl HeapSize for Leaf {}
+
+///dynamic 40 bit X-fast trie with all levels, descendant pointers and a linked leaf list
+pub struct XFT {
+    leaves: FnvHashMap<DataType, Leaf>,
+    // level l (1 to 39) at position l - 1, the root (level 40) is stored separately
+    levels: Vec<FnvHashMap<DataType, TrieNode>>,
+    root: Option<TrieNode>,
+    minimum: Option<DataType>,
+    maximum: Option<DataType>,
+}
+
+impl XFT {
+    pub fn new() -> XFT {
+        XFT {
+            leaves: FnvHashMap::default(),
+            levels: (1..BIT_LENGTH).map(|_| FnvHashMap::default()).collect(),
+            root: None,
+            minimum: None,
+            maximum: None,
+        }
+    }
+
+    ///inserts all elements, they don't need to be sorted
+    pub fn from_elements(elements: Vec<DataType>, log: &mut Log) -> XFT {
+        let mut xft = XFT::new();
+        for element in elements {
+            xft.insert(element);
+        }
+        log.log_mem("xft filled").log_time("xft filled");
+        xft
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    fn node(&self, level: usize, element: DataType) -> Option<&TrieNode> {
+        if level == BIT_LENGTH {
+            self.root.as_ref()
+        } else {
+            self.levels[level - 1].get(&calc_path(element, level, 0))
+        }
+    }
+
+    fn node_mut(&mut self, level: usize, element: DataType) -> Option<&mut TrieNode> {
+        if level == BIT_LENGTH {
+            self.root.as_mut()
+        } else {
+            self.levels[level - 1].get_mut(&calc_path(element, level, 0))
+        }
+    }
+
+    /// child of the node in level, that element belongs to
+    fn child_side(element: DataType, level: usize) -> u8 {
+        if is_left_child(calc_path(element, level - 1, 0)) { LEFT } else { RIGHT }
+    }
+
+    ///element must not be a leaf and the trie must not be empty
+    ///binary search for the lowest level, that contains a prefix of element
+    fn lowest_ancestor(&self, element: DataType) -> (usize, &TrieNode) {
+        let mut search_range = (1, BIT_LENGTH);
+        while search_range.0 != search_range.1 {
+            let level = (search_range.0 + search_range.1) / 2;
+            if self.node(level, element).is_some() {
+                search_range = (search_range.0, level);
+            } else {
+                search_range = (level + 1, search_range.1);
+            }
+        }
+        (search_range.0, self.node(search_range.0, element).unwrap())
+    }
+
+    pub fn contains(&self, element: DataType) -> bool {
+        self.leaves.contains_key(&element)
+    }
+
+    /// largest element smaller than query
+    pub fn predecessor(&self, query: DataType) -> Option<DataType> {
+        if self.root.is_none() {
+            return None;
+        }
+        if let Some(leaf) = self.leaves.get(&query) {
+            return leaf.predecessor;
+        }
+        let (level, node) = self.lowest_ancestor(query);
+        if XFT::child_side(query, level) == LEFT {
+            //left child is missing, descendant is the successor
+            self.leaves[&node.descendant].predecessor
+        } else {
+            Some(node.descendant)
+        }
+    }
+
+    /// smallest element greater than query
+    pub fn successor(&self, query: DataType) -> Option<DataType> {
+        if self.root.is_none() {
+            return None;
+        }
+        if let Some(leaf) = self.leaves.get(&query) {
+            return leaf.successor;
+        }
+        let (level, node) = self.lowest_ancestor(query);
+        if XFT::child_side(query, level) == LEFT {
+            Some(node.descendant)
+        } else {
+            //right child is missing, descendant is the predecessor
+            self.leaves[&node.descendant].successor
+        }
+    }
+
+    pub fn insert(&mut self, element: DataType) {
+        if self.leaves.contains_key(&element) {
+            return;
+        }
+        let predecessor = self.predecessor(element);
+        let successor = self.successor(element);
+        self.leaves.insert(element, Leaf { predecessor, successor });
+        match predecessor {
+            Some(predecessor) => self.leaves.get_mut(&predecessor).unwrap().successor = Some(element),
+            None => self.minimum = Some(element),
+        }
+        match successor {
+            Some(successor) => self.leaves.get_mut(&successor).unwrap().predecessor = Some(element),
+            None => self.maximum = Some(element),
+        }
+
+        for level in 1..BIT_LENGTH + 1 {
+            let side = XFT::child_side(element, level);
+            let exists = match self.node_mut(level, element) {
+                Some(node) => {
+                    node.children |= side;
+                    //element may be the new smallest or largest leaf below the existing child
+                    if node.children == LEFT && element > node.descendant {
+                        node.descendant = element;
+                    } else if node.children == RIGHT && element < node.descendant {
+                        node.descendant = element;
+                    }
+                    true
+                }
+                None => false,
+            };
+            if !exists {
+                let node = TrieNode { children: side, descendant: element };
+                if level == BIT_LENGTH {
+                    self.root = Some(node);
+                } else {
+                    self.levels[level - 1].insert(calc_path(element, level, 0), node);
+                }
+            }
+        }
+    }
+
+    pub fn delete(&mut self, element: DataType) {
+        let leaf = match self.leaves.remove(&element) {
+            Some(leaf) => leaf,
+            None => return,
+        };
+        match leaf.predecessor {
+            Some(predecessor) => self.leaves.get_mut(&predecessor).unwrap().successor = leaf.successor,
+            None => self.minimum = leaf.successor,
+        }
+        match leaf.successor {
+            Some(successor) => self.leaves.get_mut(&successor).unwrap().predecessor = leaf.predecessor,
+            None => self.maximum = leaf.predecessor,
+        }
+
+        //the leaf is removed, so its parent loses a child
+        let mut child_removed = true;
+        for level in 1..BIT_LENGTH + 1 {
+            let side = XFT::child_side(element, level);
+            let remove_node = {
+                let node = self.node_mut(level, element).unwrap();
+                if child_removed {
+                    node.children &= !side;
+                }
+                if node.children == 0 {
+                    true
+                } else {
+                    //element can't be the descendant anymore, the leaves next to it are the new borders of the subtrees
+                    if node.children == LEFT && (child_removed || node.descendant == element) {
+                        node.descendant = leaf.predecessor.unwrap();
+                    } else if node.children == RIGHT && (child_removed || node.descendant == element) {
+                        node.descendant = leaf.successor.unwrap();
+                    }
+                    false
+                }
+            };
+            if remove_node {
+                if level == BIT_LENGTH {
+                    self.root = None;
+                } else {
+                    self.levels[level - 1].remove(&calc_path(element, level, 0));
+                }
+            }
+            child_removed = remove_node;
+        }
+    }
+
+    ///prints number of nodes + relative fill level per level
+    pub fn print_stats(&self, log: &Log) {
+        let mut count = self.leaves.len();
+        log.print_result(format!("level=0\tnodes={}\trelative_to_capacity={}", count, count as f32 / 2f32.powf(BIT_LENGTH as f32)));
+        for level in 1..BIT_LENGTH {
+            let len = self.levels[level - 1].len();
+            log.print_result(format!("level={}\tnodes={}\trelative_to_capacity={}", level, len, len as f32 / 2f32.powf((BIT_LENGTH - level) as f32)));
+            count += len;
+        }
+        log.print_result(format!("level=-1\tnodes={}", count + self.root.is_some() as usize));
+        self.memory_breakdown().print(log);
+    }
+}
+
+impl TrieMemory for XFT {
+    fn memory_breakdown(&self) -> MemoryBreakdown {
+        MemoryBreakdown::of(&(), &self.leaves, &self.levels, &())
+    }
+}
+
+impl PredecessorSet<DataType> for XFT {
+    fn predecessor(&self, number: DataType) -> Option<DataType> {
+        self.predecessor(number)
+    }
+    fn insert(&mut self, element: DataType) {
+        self.insert(element)
+    }
+    fn delete(&mut self, element: DataType) {
+        self.delete(element)
+    }
+    fn successor(&self, number: DataType) -> Option<DataType> {
+        self.successor(number)
+    }
+    fn minimum(&self) -> Option<DataType> {
+        self.minimum
+    }
+    fn maximum(&self) -> Option<DataType> {
+        self.maximum
+    }
+    fn contains(&self, number: DataType) -> bool {
+        self.contains(number)
+    }
+}
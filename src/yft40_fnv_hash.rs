@@ -2,9 +2,11 @@ extern crate fnv;
 
 use args::Args;
 use log::Log;
+use heap_size::{MemoryBreakdown, TrieMemory};
 use uint::u40;
 use self::fnv::FnvHashMap;
 use predecessor_set::PredecessorSet;
+use level_keys::{calc_path, is_left_child};
 
 pub type DataType = u40;
 
@@ -138,6 +140,7 @@ impl YFT {
             count += self.lss_branch[level - 1].len();
         }
         log.print_result(format!("level=-1\tnodes={}\telements={}", count, self.elements.len()));
+        self.memory_breakdown().print(log);
     }
 
     fn calc_start_level(elements: &Vec<DataType>, min_start_level: usize, max_lss_level: usize, min_load_factor: usize) -> usize {
@@ -315,13 +318,10 @@ impl YFT {
     }
 } //impl YFT
 
-fn calc_path(position: DataType, lss_level: usize, start_level: usize) -> DataType {
-    position >> DataType::from(lss_level + start_level)
-}
-
-//returns if the node is the left of its parent
-fn is_left_child(path: DataType) -> bool {
-    path % 2 == 0
+impl TrieMemory for YFT {
+    fn memory_breakdown(&self) -> MemoryBreakdown {
+        MemoryBreakdown::of(&self.lss_top, &self.lss_leaf, &self.lss_branch, &self.elements)
+    }
 }
 
 impl PredecessorSet<DataType> for YFT {
@@ -1,7 +1,9 @@
 use args::Args;
 use log::Log;
+use heap_size::{MemoryBreakdown, TrieMemory};
 use uint::u40;
 use predecessor_set::PredecessorSet;
+use level_keys::calc_path;
 
 pub type DataType = u40;
 
@@ -55,6 +57,7 @@ impl YFT {
     pub fn print_stats(&self, log: &Log) {
         log.print_result(format!("start_level={}\tnormal_levels={}\ttop_levels={}", 0, 0, self.last_level_len));
         log.print_result(format!("level=-1\tnodes={}\telements={}", 0, self.elements.len()));
+        self.memory_breakdown().print(log);
     }
 
     ///start_level == lowest possible level
@@ -174,11 +177,13 @@ impl YFT {
     }
 } //impl YFT
 
-
-fn calc_path(position: DataType, lss_level: usize, start_level: usize) -> DataType {
-    position >> DataType::from(lss_level + start_level)
+impl TrieMemory for YFT {
+    fn memory_breakdown(&self) -> MemoryBreakdown {
+        MemoryBreakdown::of(&self.lss_top, &(), &[(); 0], &self.elements)
+    }
 }
 
+
 impl PredecessorSet<DataType> for YFT {
     ///static YFT can not insert
     fn insert(&mut self, _element: DataType) {
@@ -2,26 +2,28 @@ extern crate fnv;
 
 use args::Args;
 use log::Log;
+use heap_size::{MemoryBreakdown, TrieMemory};
+use level_stats::LevelStats;
 use uint::u40;
 use self::fnv::FnvHashMap;
 use predecessor_set::PredecessorSet;
+use packed_suffixes::PackedSuffixes;
+use level_keys::{calc_path, is_left_child};
 
 pub type DataType = u40;
-pub type SmallType = u16;
 
 const BIT_LENGTH: usize = 40;
-const SMALL_TYPE_LEN: usize = 16;
 
 /*If v is a node at a height j, then all
 the leafs descending from v will have key values
 between the quantities (i - 1)2^J + 1 and i* 2^J */
 
-///dynamic 40 bit Impl with input array stored in leafs last 16 bit only, without child pointer and binary search below xft leafs
+///dynamic 40 bit Impl with input array stored in leafs (only the last start_level bits, bit packed), without child pointer and binary search below xft leafs
 pub struct YFT {
     //predecessor of non existing subtree vec, DataType::max_value() if None (DataType::max_value() cant't be predecessor)
     lss_top: Vec<DataType>,
     // LSS Leaf Level <Position, (predecessor if there is none with same prefix, Elements that may be predecessor of prefix)>
-    lss_leaf: FnvHashMap<DataType, (DataType, Vec<SmallType>)>,
+    lss_leaf: FnvHashMap<DataType, (DataType, PackedSuffixes)>,
     // List of LSS Branch Level <Position, predecessor>
     lss_branch: Vec<FnvHashMap<DataType, DataType>>,
     //== lss leaf level
@@ -31,7 +33,7 @@ pub struct YFT {
 }
 
 impl YFT {
-    ///elements must be sorted ascending!
+    ///elements must be sorted ascending and unique!
     pub fn new(elements: Vec<DataType>, args: &Args, log: &mut Log) -> YFT {
         if elements.len() == 0 {
             panic!("Input is empty");
@@ -39,56 +41,48 @@ impl YFT {
         if elements.len() >= usize::from(DataType::max_value()) - 1 {
             panic!("Too many Elements in input");
         }
-        let mut start_level = if let Some(start_level) = args.fixed_leaf_level {
+        let stats = if args.fixed_leaf_level.is_none() || args.fixed_top_level.is_none() {
+            Some(LevelStats::new(&elements, args))
+        } else {
+            None
+        };
+        let start_level = if let Some(start_level) = args.fixed_leaf_level {
             start_level
         } else {
-            YFT::calc_start_level(&elements, args.min_start_level, BIT_LENGTH - args.max_lss_level, args.min_start_level_load_factor)
+            YFT::calc_start_level(stats.as_ref().unwrap(), args.min_start_level, BIT_LENGTH - args.max_lss_level, args.min_start_level_load_factor)
         };
-        if start_level > SMALL_TYPE_LEN - 1 {
-            println!("Start level set down to 15");
-            start_level = SMALL_TYPE_LEN;
-        }
         log.log_time("start level calculated");
         let last_level_len = if let Some(top_level) = args.fixed_top_level {
             BIT_LENGTH - top_level
         } else {
-            BIT_LENGTH - YFT::calc_lss_top_level(&elements, start_level, BIT_LENGTH - args.max_lss_level, args.max_last_level_load_factor, args.min_load_factor_difference)
+            BIT_LENGTH - YFT::calc_lss_top_level(stats.as_ref().unwrap(), start_level, BIT_LENGTH - args.max_lss_level, args.max_last_level_load_factor, args.min_load_factor_difference)
         };
         log.log_time("number of top levels calculated");
+        if let Some(stats) = stats {
+            stats.report(start_level, BIT_LENGTH - last_level_len, BIT_LENGTH, log);
+        }
+        YFT::build(elements.into_iter(), start_level, last_level_len, log)
+    }
+
+    ///builds the trie while reading elements from a stream, so the input is never stored completely
+    ///levels can't be calculated without a pass over all elements, so -f and -g are required
+    ///elements must be sorted ascending and unique!
+    pub fn from_sorted_iter<I: Iterator<Item=DataType>>(elements: I, args: &Args, log: &mut Log) -> YFT {
+        let start_level = args.fixed_leaf_level.expect("Construction from stream requires a fixed leaf level (-f)");
+        let top_level = args.fixed_top_level.expect("Construction from stream requires a fixed top level (-g)");
+        YFT::build(elements, start_level, BIT_LENGTH - top_level, log)
+    }
+
+    ///creates all levels in one pass over elements
+    fn build<I: Iterator<Item=DataType>>(elements: I, start_level: usize, last_level_len: usize, log: &mut Log) -> YFT {
         let levels = BIT_LENGTH - start_level - last_level_len;
         assert!(levels > 0 && levels < BIT_LENGTH);
 
         //initialise lss_top
         let mut lss_top = vec![DataType::max_value(); 2usize.pow(last_level_len as u32)];//Bei eingaben bis 2^32 könnte man auch u32 nehmen...
-        for (pos, value) in elements.iter().enumerate() {
-            //check array is sorted
-            debug_assert!(pos == 0 || value >= &elements[pos - 1]);
-
-            let top_pos = YFT::lss_top_position(value, last_level_len) as usize;
-            //set predecessor
-            if is_left_child(DataType::from(YFT::lss_top_position(value, last_level_len + 1))) {
-                // for queries on right child of this top level element, this element is its predecessor
-                lss_top[top_pos] = elements[pos]; //always write is correct, cause if there are values under the branch, binary search wont ask top array
-            } else if top_pos + 1 < lss_top.len() {
-                //this right child is the predecessor of the next element
-                lss_top[top_pos + 1] = elements[pos];
-            }
-        }
-        //fill skipped lss top positions
-        let mut lss_top_pos = 0;
-        let mut last_value = DataType::max_value();
-        while lss_top_pos < lss_top.len() {
-            if lss_top[lss_top_pos] == DataType::max_value() {
-                lss_top[lss_top_pos] = last_value;
-            } else {
-                last_value = lss_top[lss_top_pos];
-            }
-            lss_top_pos += 1;
-        }
-        log.log_mem("lss_branch top filled").log_time("lss_branch top filled");
 
         //initialise lss_branch
-        let mut lss_leaf: FnvHashMap<DataType, (DataType, Vec<SmallType>)> = FnvHashMap::default();
+        let mut lss_leaf: FnvHashMap<DataType, (DataType, PackedSuffixes)> = FnvHashMap::default();
         let mut lss_branch = Vec::with_capacity(levels - 1);
         for _level in 0..levels - 1 { // one less, cause leaf level is stored separately
             lss_branch.push(FnvHashMap::default());
@@ -99,18 +93,31 @@ impl YFT {
         //fill
         let mut predecessor_x_leaf: Option<DataType> = None;
         let mut predecessor = DataType::max_value();
-        for (element_array_index, value) in elements.iter().enumerate() {
-            let x_leaf_position = calc_path(*value, 0, start_level);
+        for value in elements {
+            //check input is sorted
+            debug_assert!(predecessor == DataType::max_value() || value > predecessor);
+
+            let top_pos = YFT::lss_top_position(&value, last_level_len) as usize;
+            //set predecessor
+            if is_left_child(DataType::from(YFT::lss_top_position(&value, last_level_len + 1))) {
+                // for queries on right child of this top level element, this element is its predecessor
+                lss_top[top_pos] = value; //always write is correct, cause if there are values under the branch, binary search wont ask top array
+            } else if top_pos + 1 < lss_top.len() {
+                //this right child is the predecessor of the next element
+                lss_top[top_pos + 1] = value;
+            }
+
+            let x_leaf_position = calc_path(value, 0, start_level);
             if Some(x_leaf_position) != predecessor_x_leaf {
                 //create new leaf node and insert it in level 0
-                lss_leaf.insert(x_leaf_position, (predecessor, vec![SmallType::from(*value)]));
+                lss_leaf.insert(x_leaf_position, (predecessor, PackedSuffixes::from_suffix(start_level, calc_suffix(value, start_level))));
                 //ensure predecessors array doesnt take to much space
                 if let Some(predecessor_x_leaf) = predecessor_x_leaf {
                     lss_leaf.get_mut(&predecessor_x_leaf).unwrap().1.shrink_to_fit();
                 }
             } else {
                 //add value to elements of existing leaf
-                lss_leaf.get_mut(&x_leaf_position).unwrap().1.push(SmallType::from(*value));
+                lss_leaf.get_mut(&x_leaf_position).unwrap().1.push(calc_suffix(value, start_level));
             }
 
             //insert branch nodes
@@ -118,22 +125,38 @@ impl YFT {
             //iterate through levels, until parent exists
             for i in 1..levels {
                 //path of new parent
-                let path = calc_path(*value, i, start_level);
+                let path = calc_path(value, i, start_level);
                 if is_left_child(child) {
                     // set descending pointer to rightmost leaf in left tree
-                    lss_branch[i - 1].insert(path, elements[element_array_index]);
+                    lss_branch[i - 1].insert(path, value);
                 } else {
                     // if only right tree exists, the predecessor of the first element has to be set (so don't set, if already one element is set)
                     if !lss_branch[i - 1].contains_key(&path) {
                         //max_value indicates no predecessor
-                        lss_branch[i - 1].insert(path, if element_array_index == 0 { DataType::max_value() } else { elements[element_array_index - 1] });
+                        lss_branch[i - 1].insert(path, predecessor);
                     }
                 }
                 child = path;
             }
             predecessor_x_leaf = Some(x_leaf_position as DataType);
-            predecessor = *value;
+            predecessor = value;
+        }
+        if predecessor_x_leaf.is_none() {
+            panic!("Input is empty");
+        }
+
+        //fill skipped lss top positions
+        let mut lss_top_pos = 0;
+        let mut last_value = DataType::max_value();
+        while lss_top_pos < lss_top.len() {
+            if lss_top[lss_top_pos] == DataType::max_value() {
+                lss_top[lss_top_pos] = last_value;
+            } else {
+                last_value = lss_top[lss_top_pos];
+            }
+            lss_top_pos += 1;
         }
+        log.log_mem("lss_branch top filled").log_time("lss_branch top filled");
 
         //return
         YFT { lss_top, lss_leaf, lss_branch, start_level, last_level_len }
@@ -141,20 +164,21 @@ impl YFT {
 
     pub fn add(&mut self, element: DataType) {
         let leaf_path = calc_path(element, 0, self.start_level);
+        let start_level = self.start_level;
         //TODO könnte effizienter beim iterieren gefunden werden, bzw. im fall das blatt vorhanden dort ausgelesen
         let predecessor = self.predecessor(element).unwrap_or(DataType::max_value());
         let mut add_nodes = true;
         let mut do_nothing = false;
         self.lss_leaf.entry(leaf_path).and_modify(|(_predecessor, elements)| {
             //add element to existing leaf
-            match elements.binary_search(&SmallType::from(element)) {
+            match elements.binary_search(calc_suffix(element, start_level)) {
                 Ok(_) => {
                     // element already exists, nothing to do
 //                    println!("Element {:?} already exists, nothing changed", element);
                     do_nothing = true;
                 }
                 Err(pos) => {
-                    elements.insert(pos, SmallType::from(element));
+                    elements.insert(pos, calc_suffix(element, start_level));
                     if pos < elements.len() - 1 { // one element has just been added -> -1
                         //element is not last element -> no predecessor has to be changed
                         do_nothing = true;
@@ -163,7 +187,7 @@ impl YFT {
                 }
             }
             //add element to new leaf
-        }).or_insert((predecessor, vec![SmallType::from(element)]));
+        }).or_insert((predecessor, PackedSuffixes::from_suffix(start_level, calc_suffix(element, start_level))));
 
         if do_nothing {
             return;
@@ -264,14 +288,14 @@ impl YFT {
         let mut do_nothing = false;
         match self.lss_leaf.get_mut(&leaf_path) {
             Some((predecessor, elements)) => {
-                match elements.binary_search(&SmallType::from(element)) {
+                match elements.binary_search(calc_suffix(element, self.start_level)) {
                     Ok(pos) => {
                         elements.remove(pos);
                         if elements.len() == 0 {
                             remove_node = true;
                             new_predecessor = *predecessor;
                         } else if pos == elements.len() {
-                            new_predecessor = extend_suffix(element, unsafe { *elements.get_unchecked(pos - 1) });
+                            new_predecessor = extend_suffix(element, elements.get(pos - 1), self.start_level);
                         } else {
                             //nothing else to do
                             do_nothing = true;
@@ -493,7 +517,7 @@ impl YFT {
 
     ///prints number of elements + relative fill level per lss level
     pub fn print_stats(&self, log: &Log) {
-        log.print_result(format!("start_level={}\tnormal_levels={}\ttop_levels={}", self.start_level, self.lss_branch.len() + 1, self.last_level_len));
+        log.print_result(format!("start_level={}\tnormal_levels={}\ttop_levels={}\tsuffix_bits={}", self.start_level, self.lss_branch.len() + 1, self.last_level_len, self.start_level));
         let mut len = self.lss_leaf.len();
         let mut count = len;
         log.print_result(format!("level=0\tnodes={}\trelative_to_capacity={}", len, len as f32 / 2f32.powf((BIT_LENGTH - self.start_level) as f32)));
@@ -503,13 +527,14 @@ impl YFT {
             count += self.lss_branch[level - 1].len();
         }
         log.print_result(format!("level=-1\tnodes={}", count));
+        self.memory_breakdown().print(log);
     }
 
-    fn calc_start_level(elements: &Vec<DataType>, min_start_level: usize, max_lss_level: usize, min_load_factor: usize) -> usize {
+    fn calc_start_level(stats: &LevelStats, min_start_level: usize, max_lss_level: usize, min_load_factor: usize) -> usize {
         let mut range = (min_start_level, max_lss_level - 1);
         while range.0 < range.1 {
             let candidate = (range.0 + range.1) / 2;
-            if YFT::calc_nodes_in_level(candidate, elements) / (min_load_factor as f64) >= elements.len() as f64 / 100. {
+            if stats.nodes_in_level(candidate) / (min_load_factor as f64) >= stats.len() as f64 / 100. {
                 range = (candidate + 1, range.1)
             } else {
                 range = (range.0, candidate)
@@ -522,10 +547,10 @@ impl YFT {
     /// max_lss_level == highest possible level
     /// max_load_factor == maximal percentage that a level should be filled with (between 0 and 100)
     /// min_load_factor_difference == maximal factor that a level should be less relatively filled than the last possible level (between 0 and 100)
-    fn calc_lss_top_level(elements: &Vec<DataType>, start_level: usize, max_lss_level: usize, max_load_factor: usize, min_load_factor_difference: usize) -> usize {
+    fn calc_lss_top_level(stats: &LevelStats, start_level: usize, max_lss_level: usize, max_load_factor: usize, min_load_factor_difference: usize) -> usize {
         let mut range = (start_level + 1, max_lss_level);
         //load factor can only increase if level gets higher. If it doesn't, levels can be cut.
-        let top_load_factor = YFT::calc_nodes_in_level(max_lss_level, elements) / 2f64.powf((BIT_LENGTH - max_lss_level) as f64) * (min_load_factor_difference as f64) / 100.;
+        let top_load_factor = stats.nodes_in_level(max_lss_level) / 2f64.powf((BIT_LENGTH - max_lss_level) as f64) * (min_load_factor_difference as f64) / 100.;
         let max = if top_load_factor < (max_load_factor as f64) / 100. {
             top_load_factor
         } else {
@@ -533,7 +558,7 @@ impl YFT {
         };
         while range.0 < range.1 {
             let candidate = (range.0 + range.1) / 2;
-            let load_factor = YFT::calc_nodes_in_level(candidate, elements) / 2f64.powf((BIT_LENGTH - candidate) as f64);
+            let load_factor = stats.nodes_in_level(candidate) / 2f64.powf((BIT_LENGTH - candidate) as f64);
             if load_factor < max {
                 range = (candidate + 1, range.1)
             } else {
@@ -543,20 +568,6 @@ impl YFT {
         range.1 as usize
     }
 
-    ///count how many nodes are in one level
-    fn calc_nodes_in_level(level: usize, elements: &Vec<DataType>) -> f64 { //TODO mögliche Beschleunigung durch Stichproben
-        let mut last_val = calc_path(elements[0], level, 0);
-        let mut count = 1.;
-        for value in elements {
-            let new_val = calc_path(*value, level, 0);
-            if new_val != last_val {
-                count += 1.;
-                last_val = new_val;
-            }
-        }
-        count
-    }
-
     fn lss_top_position(value: &DataType, lss_top_height: usize) -> usize {
         usize::from(*value) >> (BIT_LENGTH - lss_top_height)
     }
@@ -648,14 +659,12 @@ impl YFT {
         }
     }
 
-    fn predecessor_from_array(&self, query: DataType, predecessor: &DataType, elements: &Vec<SmallType>) -> Option<DataType> {
-        let pos = match elements.binary_search(&SmallType::from(query)) {
-            Ok(pos) => pos,
-            Err(pos) => pos
-        };
+    fn predecessor_from_array(&self, query: DataType, predecessor: &DataType, elements: &PackedSuffixes) -> Option<DataType> {
+        let suffix = calc_suffix(query, self.start_level);
+        let pos = elements.lower_bound(suffix);
         return if pos == 0 {
             //test next value greater than search one
-            debug_assert!(if let Some(successor) = elements.get(usize::from(pos)) { successor >= &SmallType::from(query) } else { true });
+            debug_assert!(elements.len() == 0 || elements.get(pos) >= suffix);
             if *predecessor == DataType::max_value() {
                 None
             } else {
@@ -665,11 +674,11 @@ impl YFT {
             }
         } else {
             //test next value greater than search one
-            debug_assert!(usize::from(pos) >= elements.len() || if let Some(successor) = elements.get(usize::from(pos)) { successor >= &SmallType::from(query) } else { true });
+            debug_assert!(pos >= elements.len() || elements.get(pos) >= suffix);
             //test value smaller than searched one
-            debug_assert!(if let Some(predecessor) = elements.get(usize::from(pos - 1)) { predecessor < &SmallType::from(query) } else { true });
+            debug_assert!(elements.get(pos - 1) < suffix);
             //get prefix via query and append it to result
-            Some(extend_suffix(query, unsafe { *elements.get_unchecked(pos - 1) }))
+            Some(extend_suffix(query, elements.get(pos - 1), self.start_level))
         };
     }
 
@@ -749,17 +758,19 @@ impl YFT {
     }
 } //impl YFT
 
-fn extend_suffix(preffix_source: DataType, suffix: SmallType) -> DataType {
-    DataType::from((usize::from(suffix)) | ((usize::from(preffix_source) >> SMALL_TYPE_LEN) << SMALL_TYPE_LEN))
+impl TrieMemory for YFT {
+    fn memory_breakdown(&self) -> MemoryBreakdown {
+        MemoryBreakdown::of(&self.lss_top, &self.lss_leaf, &self.lss_branch, &())
+    }
 }
 
-fn calc_path(position: DataType, lss_level: usize, start_level: usize) -> DataType {
-    position >> DataType::from(lss_level + start_level)
+///the last start_level bits of value, which are stored in the leaf
+fn calc_suffix(value: DataType, start_level: usize) -> u64 {
+    u64::from(value) & ((1u64 << start_level) - 1)
 }
 
-//returns if the node is the left of its parent
-fn is_left_child(path: DataType) -> bool {
-    path % 2 == 0
+fn extend_suffix(preffix_source: DataType, suffix: u64, start_level: usize) -> DataType {
+    DataType::from(suffix | ((u64::from(preffix_source) >> start_level) << start_level))
 }
 
 impl PredecessorSet<DataType> for YFT {
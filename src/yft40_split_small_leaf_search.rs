@@ -2,9 +2,12 @@ extern crate fnv;
 
 use args::Args;
 use log::Log;
+use heap_size::{MemoryBreakdown, TrieMemory};
 use uint::u40;
 use self::fnv::FnvHashMap;
 use predecessor_set::PredecessorSet;
+use simd_search;
+use level_keys::{calc_path, is_left_child};
 
 pub type DataType = u40;
 pub type SmallType = u16;
@@ -503,6 +506,7 @@ impl YFT {
             count += self.lss_branch[level - 1].len();
         }
         log.print_result(format!("level=-1\tnodes={}", count));
+        self.memory_breakdown().print(log);
     }
 
     fn calc_start_level(elements: &Vec<DataType>, min_start_level: usize, max_lss_level: usize, min_load_factor: usize) -> usize {
@@ -564,7 +568,7 @@ impl YFT {
     pub fn contains(&self, query: DataType) -> bool {
         match self.lss_leaf.get(&calc_path(query, 0, self.start_level)){
             None => false,
-            Some(&(_, ref values))=> values.binary_search(&SmallType::from(query)).is_ok() ,
+            Some(&(_, ref values))=> simd_search::contains_u16(values, SmallType::from(query)),
         }
     }
 
@@ -653,10 +657,7 @@ impl YFT {
     }
 
     fn predecessor_from_array(&self, query: DataType, predecessor: &DataType, elements: &Vec<SmallType>) -> Option<DataType> {
-        let pos = match elements.binary_search(&SmallType::from(query)) {
-            Ok(pos) => pos,
-            Err(pos) => pos
-        };
+        let pos = simd_search::lower_bound_u16(elements, SmallType::from(query));
         return if pos == 0 {
             //test next value greater than search one
             debug_assert!(if let Some(successor) = elements.get(usize::from(pos)) { successor >= &SmallType::from(query) } else { true });
@@ -753,17 +754,14 @@ impl YFT {
     }
 } //impl YFT
 
-fn extend_suffix(preffix_source: DataType, suffix: SmallType) -> DataType {
-    DataType::from((usize::from(suffix)) | ((usize::from(preffix_source) >> SMALL_TYPE_LEN) << SMALL_TYPE_LEN))
-}
-
-fn calc_path(position: DataType, lss_level: usize, start_level: usize) -> DataType {
-    position >> DataType::from(lss_level + start_level)
+impl TrieMemory for YFT {
+    fn memory_breakdown(&self) -> MemoryBreakdown {
+        MemoryBreakdown::of(&self.lss_top, &self.lss_leaf, &self.lss_branch, &())
+    }
 }
 
-//returns if the node is the left of its parent
-fn is_left_child(path: DataType) -> bool {
-    path % 2 == 0
+fn extend_suffix(preffix_source: DataType, suffix: SmallType) -> DataType {
+    DataType::from((usize::from(suffix)) | ((usize::from(preffix_source) >> SMALL_TYPE_LEN) << SMALL_TYPE_LEN))
 }
 
 impl PredecessorSet<DataType> for YFT {
@@ -2,9 +2,11 @@ extern crate rustc_hash;
 
 use args::Args;
 use log::Log;
+use heap_size::{HeapSize, MemoryBreakdown, TrieMemory};
 use uint::u40;
 use self::rustc_hash::FxHashMap;
 use predecessor_set::PredecessorSet;
+use level_keys::{calc_path, is_left_child};
 
 pub type DataType = u40;
 
@@ -143,6 +145,7 @@ impl YFT {
             count += self.lss_branch[level - 1].len();
         }
         log.print_result(format!("level=-1\tnodes={}\telements={}", count, self.elements.len()));
+        self.memory_breakdown().print(log);
     }
 
     fn calc_start_level(elements: &Vec<DataType>, min_start_level: usize, max_lss_level: usize, min_load_factor: usize) -> usize {
@@ -333,6 +336,12 @@ impl YFT {
     }
 } //impl YFT
 
+impl TrieMemory for YFT {
+    fn memory_breakdown(&self) -> MemoryBreakdown {
+        MemoryBreakdown::of(&self.lss_top, &self.lss_leaf, &self.lss_branch, &self.elements)
+    }
+}
+
 bitflags! {
     struct Children: u8 {
         const LEFT = 0b00000001;
@@ -347,6 +356,8 @@ struct TreeBranch {
     descending: DataType, //Position of predecelement in elementarray
 }
 
+impl HeapSize for TreeBranch {}
+
 impl TreeBranch {
     fn set_child(&mut self, left: bool) {
         if left {
@@ -372,14 +383,7 @@ struct TreeLeaf {
     //Position of first element in Value Vector
 }
 
-fn calc_path(position: DataType, lss_level: usize, start_level: usize) -> DataType {
-    position >> DataType::from(lss_level + start_level)
-}
-
-//returns if the node is the left of its parent
-fn is_left_child(path: DataType) -> bool {
-    path % 2 == 0
-}
+impl HeapSize for TreeLeaf {}
 
 impl PredecessorSet<DataType> for YFT {
     ///static YFT can not insert
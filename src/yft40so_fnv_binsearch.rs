@@ -1,455 +1,9 @@
 extern crate fnv;
 
-use args::Args;
-use log::Log;
-use uint::u40;
 use self::fnv::FnvHashMap;
-use predecessor_set::PredecessorSet;
+use yft40so_generic_binsearch;
 
-pub type DataType = u40;
+pub type DataType = yft40so_generic_binsearch::DataType;
 
-const BIT_LENGTH: usize = 40;
-
-/*If v is a node at a height j, then all
-the leafs descending from v will have key values
-between the quantities (i - 1)2^J + 1 and i* 2^J */
-
-///40 bit Impl with fixed group size and without child pointer and binary search below xft leafs
-pub struct YFT {
-    //predecessor of non existing subtree vec, DataType::max_value() if None (DataType::max_value() cant't be predecessor)
-    lss_top: Vec<DataType>,
-    // LSS Leaf Level (Position, Array Index)
-    lss_leaf: FnvHashMap<DataType, DataType>,
-    // List of LSS Branch Level (Position, predecessor)
-    lss_branch: Vec<FnvHashMap<DataType, DataType>>,
-    //== lss leaf level
-    start_level: usize,
-    //number of levels that are pooled into one level at the top of the xft
-    last_level_len: usize,
-    //Original input
-    elements: Vec<DataType>,
-    //size that each bucket of elements under one leaf has
-    group_size: usize,
-}
-
-impl YFT {
-    ///elements must be sorted ascending!
-    pub fn new(elements: Vec<DataType>, args: &Args, log: &mut Log) -> YFT {
-        if elements.len() < 10 {
-            panic!("Input to small");
-        }
-        if elements.len() >= usize::from(DataType::max_value()) - 1 {
-            panic!("Too many Elements in input");
-        }
-        let start_level = if let Some(start_level) = args.fixed_leaf_level {
-            start_level
-        } else {
-            YFT::calc_start_level(&elements, args.min_start_level, BIT_LENGTH - args.max_lss_level, args.min_start_level_load_factor)
-        };
-        let group_size = 2usize.pow(start_level as u32);
-        log.log_time("start level calculated");
-        let last_level_len = if let Some(top_level) = args.fixed_top_level {
-            BIT_LENGTH - top_level
-        } else {
-            BIT_LENGTH - YFT::calc_lss_top_level(&elements, start_level, BIT_LENGTH - args.max_lss_level, args.max_last_level_load_factor, args.min_load_factor_difference)
-        };
-        log.log_time("number of top levels calculated");
-        let levels = BIT_LENGTH - start_level - last_level_len;
-        assert!(levels > 0 && levels < BIT_LENGTH);
-
-        //initialise lss_top
-        let mut lss_top = vec![DataType::max_value(); 2usize.pow(last_level_len as u32)];//Bei eingaben bis 2^32 könnte man auch u32 nehmen...
-        for (pos, value) in elements.iter().enumerate().step_by(group_size) {
-            //check array is sorted
-            debug_assert!(pos == 0 || value >= &elements[pos - 1]);
-
-            let top_pos = YFT::lss_top_position(value, last_level_len) as usize;
-            //set predecessor
-            if is_left_child(DataType::from(YFT::lss_top_position(value, last_level_len + 1))) {
-                // for queries on right child of this top level element, this element is its predecessor
-                lss_top[top_pos] = DataType::from(pos);
-            } else if top_pos + 1 < lss_top.len() {
-                //this right child is the predecessor of the next element
-                lss_top[top_pos + 1] = DataType::from(pos);
-            }
-        }
-        //fill skipped lss top positions
-        let mut lss_top_pos = 0;
-        let mut last_value = DataType::max_value();
-        while lss_top_pos < lss_top.len() {
-            if lss_top[lss_top_pos] == DataType::max_value() {
-                lss_top[lss_top_pos] = last_value;
-            } else {
-                last_value = lss_top[lss_top_pos];
-            }
-            lss_top_pos += 1;
-        }
-        log.log_mem("lss_branch top filled").log_time("lss_branch top filled");
-
-        //initialise lss_branch
-        let mut lss_leaf: FnvHashMap<DataType, DataType> = FnvHashMap::default();
-        let mut lss_branch = Vec::with_capacity(levels - 1);
-        for _level in 0..levels - 1 { // one less, cause leaf level is stored separately
-            lss_branch.push(FnvHashMap::default());
-        }
-
-        log.log_mem("lss_branch initialized").log_time("lss_branch initialized");
-
-        //fill
-        let mut predecessor_x_leaf: Option<DataType> = None;
-        for (element_array_index, value) in elements.iter().enumerate().step_by(group_size) {
-            let x_leaf_position = calc_path(*value, 0, start_level);
-            if Some(x_leaf_position) != predecessor_x_leaf {
-                //create new leaf node and insert it in level 0
-                lss_leaf.insert(x_leaf_position, DataType::from(element_array_index));
-            } else {
-                panic!("Two representatives with same leaf may not happen");
-            }
-
-            //insert branch nodes
-            let mut child = x_leaf_position;
-            //iterate through levels, until parent exists
-            for i in 1..levels {
-                //path of new parent
-                let path = calc_path(*value, i, start_level);
-                if is_left_child(child) {
-                    // set descending pointer to rightmost leaf in left tree
-                    lss_branch[i - 1].insert(path, DataType::from(element_array_index));
-                } else {
-                    // if only right tree exists, the predecessor of the first element has to be set (so don't set, if already one element is set)
-                    if !lss_branch[i - 1].contains_key(&path) {
-                        //max_value indicates no predecessor
-                        lss_branch[i - 1].insert(path, if element_array_index == 0 { DataType::max_value() } else { DataType::from(element_array_index - 1) });
-                    }
-                }
-                child = path;
-            }
-            predecessor_x_leaf = Some(x_leaf_position as DataType);
-        }
-
-        //return
-        YFT { lss_top, lss_leaf, lss_branch, start_level, last_level_len, elements, group_size }
-    }
-
-    ///prints number of elements + relative fill level per lss level + Hashmap capacity
-    pub fn print_stats(&self, log: &Log) {
-        log.print_result(format!("start_level={}\tnormal_levels={}\ttop_levels={}", self.start_level, self.lss_branch.len() + 1, self.last_level_len));
-        let mut len = self.lss_leaf.len();
-        let mut count = len;
-        log.print_result(format!("level=0\tnodes={}\trelative_to_input={}\trelative_to_capacity={}", len, len as f32 / self.elements.len() as f32, len as f32 / 2f32.powf((BIT_LENGTH - self.start_level) as f32)));
-        for level in 1..self.lss_branch.len() + 1 {
-            len = self.lss_branch[level - 1].len();
-            log.print_result(format!("level={}\tnodes={}\trelative_to_input={}\trelative_to_capacity={}\tcapacity={}", level, len, len as f32 / self.elements.len() as f32, len as f32 / 2f32.powf((BIT_LENGTH - self.start_level - level) as f32), self.lss_branch[level - 1].capacity()));
-            count += self.lss_branch[level - 1].len();
-        }
-        log.print_result(format!("level=-1\tnodes={}\telements={}", count, self.elements.len()));
-    }
-
-    fn calc_start_level(elements: &Vec<DataType>, min_start_level: usize, max_lss_level: usize, min_load_factor: usize) -> usize {
-        let mut range = (min_start_level, max_lss_level - 1);
-        while range.0 < range.1 {
-            let candidate = (range.0 + range.1) / 2;
-            if YFT::calc_nodes_in_level(candidate, elements) / (min_load_factor as f64) >= elements.len() as f64 / 100. {
-                range = (candidate + 1, range.1)
-            } else {
-                range = (range.0, candidate)
-            }
-        }
-        range.1 as usize
-    }
-
-    /// start_level == lowest possible level
-    /// max_lss_level == highest possible level
-    /// max_load_factor == maximal percentage that a level should be filled with (between 0 and 100)
-    /// min_load_factor_difference == maximal factor that a level should be less relatively filled than the last possible level (between 0 and 100)
-    fn calc_lss_top_level(elements: &Vec<DataType>, start_level: usize, max_lss_level: usize, max_load_factor: usize, min_load_factor_difference: usize) -> usize {
-        let mut range = (start_level + 1, max_lss_level);
-        //load factor can only increase if level gets higher. If it doesn't, levels can be cut.
-        let top_load_factor = YFT::calc_nodes_in_level(max_lss_level, elements) / 2f64.powf((BIT_LENGTH - max_lss_level) as f64) * (min_load_factor_difference as f64) / 100.;
-        let max = if top_load_factor < (max_load_factor as f64) / 100. {
-            top_load_factor
-        } else {
-            (max_load_factor as f64) / 100.
-        };
-        while range.0 < range.1 {
-            let candidate = (range.0 + range.1) / 2;
-            let load_factor = YFT::calc_nodes_in_level(candidate, elements) / 2f64.powf((BIT_LENGTH - candidate) as f64);
-            if load_factor < max {
-                range = (candidate + 1, range.1)
-            } else {
-                range = (range.0, candidate)
-            }
-        }
-        range.1 as usize
-    }
-
-    ///count how many nodes are in one level //TODO nicht gültig für original -> raus da
-    fn calc_nodes_in_level(level: usize, elements: &Vec<DataType>) -> f64 {
-        let mut last_val = calc_path(elements[0], level, 0);
-        let mut count = 1.;
-        for value in elements {
-            let new_val = calc_path(*value, level, 0);
-            if new_val != last_val {
-                count += 1.;
-                last_val = new_val;
-            }
-        }
-        count
-    }
-
-    fn lss_top_position(value: &DataType, lss_top_length: usize) -> usize {
-        usize::from(*value) >> (BIT_LENGTH - lss_top_length)
-    }
-
-    pub fn contains(&self, query: DataType) -> bool {
-        self.predecessor(query + 1 as u32) == Some(query)
-    }
-
-    //query may not belong to existing node
-    pub fn predecessor(&self, query: DataType) -> Option<DataType> {
-        unsafe {
-            if query < *self.elements.get_unchecked(0) {
-                return None;
-            }
-            //binary search lowest ancestor for some query
-            // query 0 == lss_leaf, query len()+1 == lss_top
-            let mut search_range = (0, self.lss_branch.len() + 1);
-            while search_range.0 != search_range.1 {
-                let mut search_position = (search_range.0 + search_range.1) / 2;
-                if search_position == self.lss_branch.len() + 1 {
-                    //top level may only be used iff there are no existing nodes below in search path
-                    search_position -= 1;
-                }
-
-                if search_position == 0 {
-                    //leaf level
-                    match self.lss_leaf.get(&calc_path(query, search_position, self.start_level)) {
-                        Some(first_element) => {
-                            return self.predecessor_from_array(query, *first_element);
-                        }
-                        None => {
-                            //there is no node -> search higher
-                            search_range = (search_position + 1, search_range.1);
-                        }
-                    }
-                } else {
-                    match self.lss_branch[search_position - 1].get(&calc_path(query, search_position, self.start_level)) {
-                        Some(_branch) => {
-                            //there is a branch =>  search lower
-                            search_range = (search_range.0, search_position);
-                        }
-                        None => {
-                            //there is no node -> search higher
-                            search_range = (search_position + 1, search_range.1);
-                        }
-                    }
-                }
-            }
-
-            //search range includes now exact the lowest existing node, if there's one
-
-            if search_range.0 == self.lss_branch.len() + 1 {
-                //case there is no existing node -> look @ lss_top
-                return self.predec_lss_top(query);
-            }
-
-            if search_range.0 == 0 {
-                //leaf level
-                match self.lss_leaf.get(&calc_path(query, search_range.0, self.start_level)) {
-                    Some(first_element) => {
-                        //searched note is in Tree -> return its predecessor
-                        return self.predecessor_from_array(query, *first_element);
-                    }
-                    None => {
-                        panic!("This can't happen, cause it was checked at beginning of this method, that there is a predecessor");
-                    }
-                }
-            } else {
-                match self.lss_branch[search_range.0 - 1].get(&calc_path(query, search_range.0, self.start_level)) {
-                    Some(first_element) => {
-                        //it was checked at beginning of this method, that there is a predecessor
-                        debug_assert!(*first_element != DataType::max_value());
-                        //first missing node in xft would be left child -> descending shows successor
-                        return self.predecessor_from_array(query, *first_element);
-                    }
-                    None => {
-                        panic!("This can't happen, cause it was checked at beginning of this method, that there is a predecessor");
-                    }
-                }
-            }
-        }
-    }
-
-    ///can only be used, if there is no existing node below
-    fn predec_lss_top(&self, query: DataType) -> Option<DataType> {
-        // assert not in lss branch
-        debug_assert!(self.lss_branch.len() == 0 || match self.lss_branch[self.lss_branch.len() - 1].get(&calc_path(query, BIT_LENGTH - self.last_level_len - 1 - self.start_level, self.start_level)) {
-            None => true,
-            Some(_) => false
-        });
-        debug_assert!(self.lss_branch.len() > 0 || match self.lss_leaf.get(&calc_path(query, BIT_LENGTH - self.last_level_len - 1 - self.start_level, self.start_level)) {
-            None => true,
-            Some(_) => false
-        });
-        unsafe {
-            let pos = *self.lss_top.get_unchecked(YFT::lss_top_position(&query, self.last_level_len));
-            if pos == DataType::max_value() {
-                panic!("This can't happen, cause it was checked at beginning predecessor method, that there is a predecessor");
-            } else {
-                return self.predecessor_from_array(query, pos);
-            }
-        }
-    }
-
-    fn predecessor_from_array(&self, query: DataType, index: DataType) -> Option<DataType> {
-        //get bounds for binary search in elements array
-        let left = if index <= self.group_size as u64 {
-            0
-        } else {
-            // predecessor can be smaller first query in leaf
-            usize::from(index) - self.group_size
-        };
-        let right = if usize::from(index) + self.group_size * 2 >= self.elements.len() {
-            self.elements.len()
-        } else {
-            usize::from(index) + self.group_size * 2
-        };
-        let pos = match self.elements.get(left..right).unwrap().binary_search(&query) {
-            Ok(pos) => pos + left,
-            Err(pos) => pos + left
-        };
-        if pos > 0 {
-            //test next query greater than search one
-            debug_assert!(usize::from(pos) >= self.elements.len() || if let Some(successor) = self.elements.get(usize::from(pos)) { successor >= &query } else { true });
-            //test query smaller than searched one
-            debug_assert!(if let Some(predecessor) = self.elements.get(usize::from(pos - 1)) { predecessor < &query } else { true });
-            debug_assert!(usize::from(pos - 1) < self.elements.len());
-            unsafe {
-                Some(*self.elements.get_unchecked(pos - 1))
-            }
-        } else {
-            debug_assert!(self.elements[0] >= query);
-            None
-        }
-    }
-
-
-    /// position may not belong to existing node
-    /// exit point (0 leaf, x level, 42 top, 43 begin)
-    /// number of binary search steps
-    /// number of hash table misses
-    pub fn predecessor_with_stats(&self, query: DataType) -> (Option<DataType>, u32, u32, u32) {
-        let mut search_steps = 0;
-        let mut hash_miss = 0;
-        unsafe {
-            if query < *self.elements.get_unchecked(0) {
-                return (None, 0, search_steps, hash_miss);
-            }
-            //binary search lowest ancestor for some query
-            // query 0 == lss_leaf, query len()+1 == lss_top
-            let mut search_range = (0, self.lss_branch.len() + 1);
-            while search_range.0 != search_range.1 {
-                search_steps += 1;
-                let mut search_position = (search_range.0 + search_range.1) / 2;
-                if search_position == self.lss_branch.len() + 1 {
-                    //top level may only be used iff there are no existing nodes below in search path
-                    search_position -= 1;
-                }
-
-                if search_position == 0 {
-                    //leaf level
-                    match self.lss_leaf.get(&calc_path(query, search_position, self.start_level)) {
-                        Some(first_element) => {
-                            return (self.predecessor_from_array(query, *first_element), 0, search_steps, hash_miss);
-                        }
-                        None => {
-                            hash_miss += 1;
-                            //there is no node -> search higher
-                            search_range = (search_position + 1, search_range.1);
-                        }
-                    }
-                } else {
-                    match self.lss_branch[search_position - 1].get(&calc_path(query, search_position, self.start_level)) {
-                        Some(_branch) => {
-                            //there is a branch =>  search lower
-                            search_range = (search_range.0, search_position);
-                        }
-                        None => {
-                            hash_miss += 1;
-                            //there is no node -> search higher
-                            search_range = (search_position + 1, search_range.1);
-                        }
-                    }
-                }
-            }
-
-            //search range includes now exact the lowest existing node, if there's one
-
-            if search_range.0 == self.lss_branch.len() + 1 {
-                //case there is no existing node -> look @ lss_top
-                return (self.predec_lss_top(query), 42, search_steps, hash_miss);
-            }
-
-            if search_range.0 == 0 {
-                //leaf level
-                match self.lss_leaf.get(&calc_path(query, search_range.0, self.start_level)) {
-                    Some(first_element) => {
-                        //searched note is in Tree -> return its predecessor
-                        return (self.predecessor_from_array(query, *first_element), 0, search_steps, hash_miss);
-                    }
-                    None => {
-                        panic!("This can't happen, cause it was checked at beginning of this method, that there is a predecessor");
-                    }
-                }
-            } else {
-                match self.lss_branch[search_range.0 - 1].get(&calc_path(query, search_range.0, self.start_level)) {
-                    Some(first_element) => {
-                        //it was checked at beginning of this method, that there is a predecessor
-                        debug_assert!(*first_element != DataType::max_value());
-                        //first missing node in xft would be left child -> descending shows successor
-                        return (self.predecessor_from_array(query, *first_element), search_range.0 as u32, search_steps, hash_miss);
-                    }
-                    None => {
-                        panic!("This can't happen, cause it was checked at beginning of this method, that there is a predecessor");
-                    }
-                }
-            }
-        }
-    }
-} //impl YFT
-
-fn calc_path(position: DataType, lss_level: usize, start_level: usize) -> DataType {
-    position >> DataType::from(lss_level + start_level)
-}
-
-//returns if the node is the left of its parent
-fn is_left_child(path: DataType) -> bool {
-    path % 2 == 0
-}
-
-impl PredecessorSet<DataType> for YFT {
-    ///static YFT can not insert
-    fn insert(&mut self, _element: DataType) {
-        panic!("static YFT can not insert");
-    }
-    ///static YFT can not delete
-    fn delete(&mut self, _element: DataType) {
-        panic!("static YFT can not delete");
-    }
-    fn predecessor(&self, number: DataType) -> Option<DataType> {
-        self.predecessor(number)
-    }
-    ///not implemented yet
-    fn successor(&self, _number: DataType) -> Option<DataType> {
-        panic!("sucessor not implemented yet")
-    }
-    fn minimum(&self) -> Option<DataType> {
-        panic!("minimum not implemented yet")
-    }
-    fn maximum(&self) -> Option<DataType> {
-        panic!("maximum not implemented yet")
-    }
-    fn contains(&self, number: DataType) -> bool {
-        self.contains(number)
-    }
-}
\ No newline at end of file
+///40 bit Impl with fixed group size, FNV hash map, without child pointer and binary search below xft leafs
+pub type YFT = yft40so_generic_binsearch::YFT<FnvHashMap<DataType, DataType>>;
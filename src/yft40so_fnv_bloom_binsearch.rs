@@ -0,0 +1,10 @@
+extern crate fnv;
+
+use self::fnv::FnvHashMap;
+use bloom_filter::FilteredLevelMap;
+use yft40so_generic_binsearch;
+
+pub type DataType = yft40so_generic_binsearch::DataType;
+
+///40 bit Impl with fixed group size, FNV hash map with bloom filter per level, without child pointer and binary search below xft leafs
+pub type YFT = yft40so_generic_binsearch::YFT<FilteredLevelMap<FnvHashMap<DataType, DataType>>>;
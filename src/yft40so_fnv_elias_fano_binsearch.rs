@@ -0,0 +1,10 @@
+extern crate fnv;
+
+use self::fnv::FnvHashMap;
+use elias_fano::EliasFano;
+use yft40so_generic_binsearch;
+
+pub type DataType = yft40so_generic_binsearch::DataType;
+
+///40 bit Impl with fixed group size, FNV hash map, elias-fano coded element array, without child pointer and rank search below xft leafs
+pub type YFT = yft40so_generic_binsearch::YFT<FnvHashMap<DataType, DataType>, EliasFano>;
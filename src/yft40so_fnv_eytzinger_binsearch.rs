@@ -0,0 +1,10 @@
+extern crate fnv;
+
+use self::fnv::FnvHashMap;
+use leaf_layout::EytzingerLayout;
+use yft40so_generic_binsearch;
+
+pub type DataType = yft40so_generic_binsearch::DataType;
+
+///40 bit Impl with fixed group size, FNV hash map, leaf groups in eytzinger order, without child pointer and binary search below xft leafs
+pub type YFT = yft40so_generic_binsearch::YFT<FnvHashMap<DataType, DataType>, EytzingerLayout>;
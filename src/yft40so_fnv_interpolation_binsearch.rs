@@ -0,0 +1,10 @@
+extern crate fnv;
+
+use self::fnv::FnvHashMap;
+use leaf_layout::InterpolationLayout;
+use yft40so_generic_binsearch;
+
+pub type DataType = yft40so_generic_binsearch::DataType;
+
+///40 bit Impl with fixed group size, FNV hash map, interpolation search in the element array, without child pointer
+pub type YFT = yft40so_generic_binsearch::YFT<FnvHashMap<DataType, DataType>, InterpolationLayout>;
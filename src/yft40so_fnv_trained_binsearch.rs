@@ -0,0 +1,11 @@
+extern crate fnv;
+
+use self::fnv::FnvHashMap;
+use leaf_layout::SortedLayout;
+use level_search::TrainedSearch;
+use yft40so_generic_binsearch;
+
+pub type DataType = yft40so_generic_binsearch::DataType;
+
+///40 bit Impl with fixed group size, FNV hash map, level search tree trained with the exit levels of sample queries, without child pointer
+pub type YFT = yft40so_generic_binsearch::YFT<FnvHashMap<DataType, DataType>, SortedLayout, TrainedSearch>;
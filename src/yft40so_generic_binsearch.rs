@@ -0,0 +1,562 @@
+extern crate rayon;
+
+use args::Args;
+use log::Log;
+use heap_size::{HeapSize, MemoryBreakdown, TrieMemory};
+use level_stats::LevelStats;
+use std::mem::size_of;
+use uint::u40;
+use level_map::{LevelMap, LevelMapConfig, LookupStats};
+use predecessor_set::PredecessorSet;
+use leaf_layout::{ElementLayout, SortedLayout};
+use level_search::{LevelSearch, MidpointSearch};
+use level_keys::{calc_path, is_left_child};
+use self::rayon::prelude::*;
+
+pub type DataType = u40;
+
+const BIT_LENGTH: usize = 40;
+
+/*If v is a node at a height j, then all
+the leafs descending from v will have key values
+between the quantities (i - 1)2^J + 1 and i* 2^J */
+
+///40 bit Impl with fixed group size and without child pointer and binary search below xft leafs
+///generic over the hash table, that is used for the lss levels, the layout of the element array and the order of the level search
+pub struct YFT<M: LevelMap<DataType, DataType>, L: ElementLayout = SortedLayout, S: LevelSearch = MidpointSearch> {
+    //predecessor of non existing subtree vec, DataType::max_value() if None (DataType::max_value() cant't be predecessor)
+    lss_top: Vec<DataType>,
+    // LSS Leaf Level (Position, Array Index)
+    lss_leaf: M,
+    // List of LSS Branch Level (Position, predecessor)
+    lss_branch: Vec<M>,
+    //== lss leaf level
+    start_level: usize,
+    //number of levels that are pooled into one level at the top of the xft
+    last_level_len: usize,
+    //Original input
+    elements: L,
+    //size that each bucket of elements under one leaf has
+    group_size: usize,
+    //chooses the next level of the binary search for the lowest existing ancestor
+    level_search: S,
+}
+
+impl<M: LevelMap<DataType, DataType>, L: ElementLayout, S: LevelSearch> YFT<M, L, S> {
+    ///elements must be sorted ascending!
+    pub fn new(elements: Vec<DataType>, args: &Args, log: &mut Log) -> YFT<M, L, S> {
+        let (start_level, last_level_len) = Self::calc_levels(&elements, args, log);
+        let group_size = 2usize.pow(start_level as u32);
+        let levels = BIT_LENGTH - start_level - last_level_len;
+
+        //initialise lss_top
+        let mut lss_top = vec![DataType::max_value(); 2usize.pow(last_level_len as u32)];//Bei eingaben bis 2^32 könnte man auch u32 nehmen...
+        Self::fill_lss_top(&mut lss_top, 0, &elements, last_level_len, group_size);
+        log.log_mem("lss_branch top filled").log_time("lss_branch top filled");
+
+        //generate leaf level
+        let config = LevelMapConfig::from_args(args);
+        let (keys, values) = Self::level_pairs(&elements, 0, start_level, group_size);
+        let lss_leaf = M::from_sorted_pairs_with_config(keys, values, &config);
+        log.log_mem("lss_leaf filled").log_time("lss_leaf filled");
+
+        //generate branch levels
+        let mut lss_branch = Vec::with_capacity(levels - 1);
+        for i in 1..levels { // one less, cause leaf level is stored separately
+            let (keys, values) = Self::level_pairs(&elements, i, start_level, group_size);
+            lss_branch.push(M::from_sorted_pairs_with_config(keys, values, &config));
+            log.log_mem(format!("lss_branch[{}] filled", i).as_str()).log_time(format!("lss_branch[{}] filled", i).as_str());
+        }
+
+        let elements = L::from_sorted(elements, group_size);
+        log.log_mem("elements arranged").log_time("elements arranged");
+
+        //return
+        YFT { lss_top, lss_leaf, lss_branch, start_level, last_level_len, elements, group_size, level_search: S::new(levels) }
+    }
+
+    ///returns (start_level, last_level_len)
+    fn calc_levels(elements: &Vec<DataType>, args: &Args, log: &mut Log) -> (usize, usize) {
+        if elements.len() < 10 {
+            panic!("Input to small");
+        }
+        if elements.len() >= usize::from(DataType::max_value()) - 1 {
+            panic!("Too many Elements in input");
+        }
+        let stats = if args.fixed_leaf_level.is_none() || args.fixed_top_level.is_none() || args.memory_budget.is_some() {
+            Some(LevelStats::new(elements, args))
+        } else {
+            None
+        };
+        let start_level = if let Some(start_level) = args.fixed_leaf_level {
+            start_level
+        } else {
+            Self::calc_start_level(stats.as_ref().unwrap(), args.min_start_level, BIT_LENGTH - args.max_lss_level, args.min_start_level_load_factor)
+        };
+        log.log_time("start level calculated");
+        let last_level_len = if let Some(top_level) = args.fixed_top_level {
+            BIT_LENGTH - top_level
+        } else {
+            BIT_LENGTH - Self::calc_lss_top_level(stats.as_ref().unwrap(), start_level, BIT_LENGTH - args.max_lss_level, args.max_last_level_load_factor, args.min_load_factor_difference)
+        };
+        log.log_time("number of top levels calculated");
+        let (start_level, last_level_len) = if let Some(budget) = args.memory_budget {
+            let (start_level, top_level) = Self::fit_into_budget(stats.as_ref().unwrap(), start_level, BIT_LENGTH - last_level_len, budget, args);
+            log.log_time("levels fitted into memory budget");
+            (start_level, BIT_LENGTH - top_level)
+        } else {
+            (start_level, last_level_len)
+        };
+        if let Some(stats) = stats {
+            stats.report(start_level, BIT_LENGTH - last_level_len, BIT_LENGTH, log);
+        }
+        let levels = BIT_LENGTH - start_level - last_level_len;
+        assert!(levels > 0 && levels < BIT_LENGTH);
+        (start_level, last_level_len)
+    }
+
+    ///predicted bytes of a trie with the given levels (element layout, lss_top and all level maps)
+    ///the number of nodes per level is bounded by the number of leaf groups, because only representatives are stored
+    pub fn estimate_size(stats: &LevelStats, start_level: usize, top_level: usize, config: &LevelMapConfig) -> usize {
+        let groups = ((stats.len() + (1 << start_level) - 1) >> start_level) as f64;
+        let levels: usize = (start_level..top_level).map(|level| {
+            M::estimate_heap_size(stats.nodes_in_level(level).min(groups) as usize, config)
+        }).sum();
+        L::estimate_heap_size(stats.len(), stats.max(), 1 << start_level) + 2usize.pow((BIT_LENGTH - top_level) as u32) * size_of::<DataType>() + levels + S::estimate_heap_size(top_level - start_level)
+    }
+
+    ///returns the first (start_level, top_level), that fits into budget bytes
+    ///levels are raised starting from the calculated ones, fixed levels (-f, -g) are kept
+    fn fit_into_budget(stats: &LevelStats, start_level: usize, top_level: usize, budget: usize, args: &Args) -> (usize, usize) {
+        let config = LevelMapConfig::from_args(args);
+        let start_levels = if args.fixed_leaf_level.is_some() { start_level..start_level + 1 } else { start_level..BIT_LENGTH - 1 };
+        let mut smallest = usize::max_value();
+        for start in start_levels {
+            let top_levels = if args.fixed_top_level.is_some() { top_level..top_level + 1 } else { top_level.max(start + 1)..BIT_LENGTH };
+            for top in top_levels {
+                if top <= start {
+                    continue;
+                }
+                let size = Self::estimate_size(stats, start, top, &config);
+                if size <= budget {
+                    return (start, top);
+                }
+                smallest = smallest.min(size);
+            }
+        }
+        panic!("Memory budget of {} bytes is too small, the smallest possible trie needs about {} bytes", budget, smallest);
+    }
+
+    ///fills lss_top[offset..offset + range.len()] (range is a part of lss_top)
+    ///each top position gets the array index of the last representative (first element of a group), that is its predecessor
+    ///disjoint ranges can be filled independently
+    fn fill_lss_top(range: &mut [DataType], offset: usize, elements: &Vec<DataType>, last_level_len: usize, group_size: usize) {
+        // top position, that a representative is predecessor for (non decreasing, because elements are sorted)
+        let target = |representative: usize| -> usize {
+            let value = &elements[representative * group_size];
+            let top_pos = Self::lss_top_position(value, last_level_len);
+            if is_left_child(DataType::from(Self::lss_top_position(value, last_level_len + 1))) {
+                // for queries on right child of this top level element, this element is its predecessor
+                top_pos
+            } else {
+                //this right child is the predecessor of the next element
+                top_pos + 1
+            }
+        };
+        let representatives = (elements.len() + group_size - 1) / group_size;
+        //first representative, that belongs into this range
+        let (mut low, mut high) = (0, representatives);
+        while low < high {
+            let middle = (low + high) / 2;
+            if target(middle) < offset {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        let mut representative = low;
+        //predecessor of positions before the first one of this range
+        let mut last_value = if representative == 0 { DataType::max_value() } else { DataType::from((representative - 1) * group_size) };
+        for (pos, top) in range.iter_mut().enumerate() {
+            while representative < representatives && target(representative) <= offset + pos {
+                //check array is sorted
+                debug_assert!(representative == 0 || elements[representative * group_size] >= elements[representative * group_size - 1]);
+                last_value = DataType::from(representative * group_size);
+                representative += 1;
+            }
+            *top = last_value;
+        }
+    }
+
+    ///sorted (path, array index) pairs of one level, level 0 is the leaf level
+    fn level_pairs(elements: &Vec<DataType>, level: usize, start_level: usize, group_size: usize) -> (Vec<DataType>, Vec<DataType>) {
+        let mut keys = Vec::with_capacity(elements.len() / group_size / 2usize.pow(level as u32) + 1);
+        let mut values = Vec::with_capacity(elements.len() / group_size / 2usize.pow(level as u32) + 1);
+        if level == 0 {
+            for (element_array_index, value) in elements.iter().enumerate().step_by(group_size) {
+                let leaf_position = calc_path(*value, 0, start_level);
+                if keys.last() == Some(&leaf_position) {
+                    panic!("Two representatives with same leaf may not happen");
+                }
+                keys.push(leaf_position);
+                values.push(DataType::from(element_array_index));
+            }
+            return (keys, values);
+        }
+        for (element_array_index, value) in elements.iter().enumerate().step_by(group_size) {
+            let path = calc_path(*value, level, start_level);
+            if is_left_child(calc_path(*value, level - 1, start_level)) {
+                // set descending pointer to rightmost leaf in left tree
+                if keys.last() == Some(&path) {
+                    *values.last_mut().unwrap() = DataType::from(element_array_index);
+                } else {
+                    keys.push(path);
+                    values.push(DataType::from(element_array_index));
+                }
+            } else if keys.last() != Some(&path) {
+                // if only right tree exists, the predecessor of the first element has to be set
+                //max_value indicates no predecessor
+                keys.push(path);
+                values.push(if element_array_index == 0 { DataType::max_value() } else { DataType::from(element_array_index - 1) });
+            }
+        }
+        (keys, values)
+    }
+
+    ///number of cut off bits of the leaf level
+    pub fn start_level(&self) -> usize {
+        self.start_level
+    }
+
+    ///prints number of elements + relative fill level per lss level + hash table size
+    pub fn print_stats(&self, log: &Log) {
+        log.print_result(format!("start_level={}\tnormal_levels={}\ttop_levels={}", self.start_level, self.lss_branch.len() + 1, self.last_level_len));
+        let mut len = self.lss_leaf.len();
+        let mut count = len;
+        log.print_result(format!("level=0\tnodes={}\trelative_to_input={}\trelative_to_capacity={}\tbytes={}", len, len as f32 / self.elements.len() as f32, len as f32 / 2f32.powf((BIT_LENGTH - self.start_level) as f32), self.lss_leaf.heap_size()));
+        for level in 1..self.lss_branch.len() + 1 {
+            len = self.lss_branch[level - 1].len();
+            log.print_result(format!("level={}\tnodes={}\trelative_to_input={}\trelative_to_capacity={}\tbytes={}", level, len, len as f32 / self.elements.len() as f32, len as f32 / 2f32.powf((BIT_LENGTH - self.start_level - level) as f32), self.lss_branch[level - 1].heap_size()));
+            count += self.lss_branch[level - 1].len();
+        }
+        log.print_result(format!("level=-1\tnodes={}\telements={}", count, self.elements.len()));
+        self.elements.print_stats(log);
+        self.level_search.print_stats(log);
+        self.memory_breakdown().print(log);
+    }
+
+    fn calc_start_level(stats: &LevelStats, min_start_level: usize, max_lss_level: usize, min_load_factor: usize) -> usize {
+        let mut range = (min_start_level, max_lss_level - 1);
+        while range.0 < range.1 {
+            let candidate = (range.0 + range.1) / 2;
+            if stats.nodes_in_level(candidate) / (min_load_factor as f64) >= stats.len() as f64 / 100. {
+                range = (candidate + 1, range.1)
+            } else {
+                range = (range.0, candidate)
+            }
+        }
+        range.1 as usize
+    }
+
+    /// start_level == lowest possible level
+    /// max_lss_level == highest possible level
+    /// max_load_factor == maximal percentage that a level should be filled with (between 0 and 100)
+    /// min_load_factor_difference == maximal factor that a level should be less relatively filled than the last possible level (between 0 and 100)
+    fn calc_lss_top_level(stats: &LevelStats, start_level: usize, max_lss_level: usize, max_load_factor: usize, min_load_factor_difference: usize) -> usize {
+        let mut range = (start_level + 1, max_lss_level);
+        //load factor can only increase if level gets higher. If it doesn't, levels can be cut.
+        let top_load_factor = stats.nodes_in_level(max_lss_level) / 2f64.powf((BIT_LENGTH - max_lss_level) as f64) * (min_load_factor_difference as f64) / 100.;
+        let max = if top_load_factor < (max_load_factor as f64) / 100. {
+            top_load_factor
+        } else {
+            (max_load_factor as f64) / 100.
+        };
+        while range.0 < range.1 {
+            let candidate = (range.0 + range.1) / 2;
+            let load_factor = stats.nodes_in_level(candidate) / 2f64.powf((BIT_LENGTH - candidate) as f64);
+            if load_factor < max {
+                range = (candidate + 1, range.1)
+            } else {
+                range = (range.0, candidate)
+            }
+        }
+        range.1 as usize
+    }
+
+    fn lss_top_position(value: &DataType, lss_top_length: usize) -> usize {
+        usize::from(*value) >> (BIT_LENGTH - lss_top_length)
+    }
+
+    pub fn contains(&self, query: DataType) -> bool {
+        self.predecessor(query + 1 as u32) == Some(query)
+    }
+
+    //query may not belong to existing node
+    pub fn predecessor(&self, query: DataType) -> Option<DataType> {
+        match self.rank(query) {
+            0 => None,
+            rank => Some(self.elements.get(rank - 1)),
+        }
+    }
+
+    /// number of elements smaller than query
+    pub fn rank(&self, query: DataType) -> usize {
+        if query < self.elements.get(0) {
+            return 0;
+        }
+        let (_, _, _, first_element) = self.locate(query, None);
+        self.rank_from_array(query, first_element)
+    }
+
+    /// binary search for the lowest existing ancestor of query, query must not be smaller than the first element
+    /// exit point (0 leaf, x level, 42 top)
+    /// number of binary search steps
+    /// number of hash table misses
+    /// position, that the search in the element array starts from
+    /// the lookups are counted in stats, if it is given
+    #[inline]
+    fn locate(&self, query: DataType, mut stats: Option<&mut LookupStats>) -> (u32, u32, u32, DataType) {
+        let mut search_steps = 0;
+        let mut hash_miss = 0;
+        //binary search lowest ancestor for some query
+        // query 0 == lss_leaf, query len()+1 == lss_top
+        let mut search_range = (0, self.lss_branch.len() + 1);
+        while search_range.0 != search_range.1 {
+            search_steps += 1;
+            let mut search_position = self.level_search.position(search_range.0, search_range.1);
+            if search_position == self.lss_branch.len() + 1 {
+                //top level may only be used iff there are no existing nodes below in search path
+                search_position -= 1;
+            }
+
+            if search_position == 0 {
+                //leaf level
+                match Self::level_get(&self.lss_leaf, &calc_path(query, search_position, self.start_level), &mut stats) {
+                    Some(first_element) => {
+                        return (0, search_steps, hash_miss, *first_element);
+                    }
+                    None => {
+                        hash_miss += 1;
+                        //there is no node -> search higher
+                        search_range = (search_position + 1, search_range.1);
+                    }
+                }
+            } else {
+                match Self::level_get(&self.lss_branch[search_position - 1], &calc_path(query, search_position, self.start_level), &mut stats) {
+                    Some(_branch) => {
+                        //there is a branch =>  search lower
+                        search_range = (search_range.0, search_position);
+                    }
+                    None => {
+                        hash_miss += 1;
+                        //there is no node -> search higher
+                        search_range = (search_position + 1, search_range.1);
+                    }
+                }
+            }
+        }
+
+        //search range includes now exact the lowest existing node, if there's one
+
+        if search_range.0 == self.lss_branch.len() + 1 {
+            //case there is no existing node -> look @ lss_top
+            return (42, search_steps, hash_miss, self.lss_top_first_element(query));
+        }
+
+        if search_range.0 == 0 {
+            //leaf level
+            match self.lss_leaf.get(&calc_path(query, search_range.0, self.start_level)) {
+                Some(first_element) => {
+                    //searched note is in Tree -> return its predecessor
+                    (0, search_steps, hash_miss, *first_element)
+                }
+                None => {
+                    panic!("This can't happen, cause it was checked at beginning of this method, that there is a predecessor");
+                }
+            }
+        } else {
+            match self.lss_branch[search_range.0 - 1].get(&calc_path(query, search_range.0, self.start_level)) {
+                Some(first_element) => {
+                    //it was checked at beginning of this method, that there is a predecessor
+                    debug_assert!(*first_element != DataType::max_value());
+                    //first missing node in xft would be left child -> descending shows successor
+                    (search_range.0 as u32, search_steps, hash_miss, *first_element)
+                }
+                None => {
+                    panic!("This can't happen, cause it was checked at beginning of this method, that there is a predecessor");
+                }
+            }
+        }
+    }
+
+    ///lookup in one level, that is counted in stats, if it is given
+    #[inline]
+    fn level_get<'a>(level: &'a M, key: &DataType, stats: &mut Option<&mut LookupStats>) -> Option<&'a DataType> {
+        match *stats {
+            Some(ref mut stats) => level.get_with_stats(key, stats),
+            None => level.get(key),
+        }
+    }
+
+    ///can only be used, if there is no existing node below
+    ///position of the element array, that lss_top stores for query
+    fn lss_top_first_element(&self, query: DataType) -> DataType {
+        // assert not in lss branch
+        debug_assert!(self.lss_branch.len() == 0 || match self.lss_branch[self.lss_branch.len() - 1].get(&calc_path(query, BIT_LENGTH - self.last_level_len - 1 - self.start_level, self.start_level)) {
+            None => true,
+            Some(_) => false
+        });
+        debug_assert!(self.lss_branch.len() > 0 || match self.lss_leaf.get(&calc_path(query, BIT_LENGTH - self.last_level_len - 1 - self.start_level, self.start_level)) {
+            None => true,
+            Some(_) => false
+        });
+        let pos = unsafe { *self.lss_top.get_unchecked(Self::lss_top_position(&query, self.last_level_len)) };
+        if pos == DataType::max_value() {
+            panic!("This can't happen, cause it was checked at beginning predecessor method, that there is a predecessor");
+        }
+        pos
+    }
+
+    fn predecessor_from_array(&self, query: DataType, index: DataType) -> Option<DataType> {
+        match self.rank_from_array(query, index) {
+            0 => None,
+            rank => Some(self.elements.get(rank - 1)),
+        }
+    }
+
+    /// element with the given rank (position in sorted order)
+    pub fn select(&self, rank: usize) -> Option<DataType> {
+        if rank < self.elements.len() {
+            Some(self.elements.get(rank))
+        } else {
+            None
+        }
+    }
+
+    fn rank_from_array(&self, query: DataType, index: DataType) -> usize {
+        //get bounds for binary search in elements array
+        let left = if index <= self.group_size as u64 {
+            0
+        } else {
+            // predecessor can be smaller first query in leaf
+            usize::from(index) - self.group_size
+        };
+        let right = if usize::from(index) + self.group_size * 2 >= self.elements.len() {
+            self.elements.len()
+        } else {
+            usize::from(index) + self.group_size * 2
+        };
+        let pos = self.elements.lower_bound(query, left, right);
+        //test next query greater than search one
+        debug_assert!(pos >= self.elements.len() || self.elements.get(pos) >= query);
+        //test query smaller than searched one
+        debug_assert!(pos == 0 || self.elements.get(pos - 1) < query);
+        pos
+    }
+
+
+    ///counts the exit levels of the queries (like predecessor_with_stats) and trains the level search with them
+    pub fn train_level_search(&mut self, queries: &[DataType]) {
+        let levels = self.lss_branch.len() + 1;
+        let mut frequencies = vec![0; levels + 1];
+        for query in queries {
+            //queries before the first element don't search
+            if *query >= self.elements.get(0) {
+                let (exit, _, _, _) = self.locate(*query, None);
+                frequencies[if exit == 42 { levels } else { exit as usize }] += 1;
+            }
+        }
+        self.level_search.train(&frequencies);
+    }
+
+    /// position may not belong to existing node
+    /// exit point (0 leaf, x level, 42 top, 43 begin)
+    /// number of binary search steps
+    /// number of hash table misses
+    /// the approximate pre checks of the level lookups are counted in lookup_stats
+    pub fn predecessor_with_stats(&self, query: DataType, lookup_stats: &mut LookupStats) -> (Option<DataType>, u32, u32, u32) {
+        if query < self.elements.get(0) {
+            return (None, 0, 0, 0);
+        }
+        let (exit, search_steps, hash_miss, first_element) = self.locate(query, Some(lookup_stats));
+        (self.predecessor_from_array(query, first_element), exit, search_steps, hash_miss)
+    }
+} //impl YFT
+
+impl<M: LevelMap<DataType, DataType>, L: ElementLayout, S: LevelSearch> TrieMemory for YFT<M, L, S> {
+    fn memory_breakdown(&self) -> MemoryBreakdown {
+        MemoryBreakdown {
+            lss_top: self.lss_top.heap_size(),
+            lss_leaf: self.lss_leaf.heap_size(),
+            lss_branch: self.lss_branch.iter().map(|level| level.heap_size()).collect(),
+            elements: self.elements.heap_size(),
+            level_search: self.level_search.heap_size(),
+            estimated: self.lss_leaf.heap_size_is_estimated(),
+        }
+    }
+}
+
+impl<M: LevelMap<DataType, DataType> + Send, L: ElementLayout, S: LevelSearch> YFT<M, L, S> {
+    ///like new, but lss_top and all levels are built concurrently with args.threads threads (0 = one per core)
+    ///all levels are in memory at the same time, before they are moved into the trie
+    ///elements must be sorted ascending!
+    pub fn new_parallel(elements: Vec<DataType>, args: &Args, log: &mut Log) -> YFT<M, L, S> {
+        if args.threads == 1 {
+            return Self::new(elements, args, log);
+        }
+        let (start_level, last_level_len) = Self::calc_levels(&elements, args, log);
+        let group_size = 2usize.pow(start_level as u32);
+        let levels = BIT_LENGTH - start_level - last_level_len;
+        let pool = rayon::ThreadPoolBuilder::new().num_threads(args.threads).build().unwrap();
+
+        //initialise lss_top
+        let mut lss_top = vec![DataType::max_value(); 2usize.pow(last_level_len as u32)];
+        let chunk_len = lss_top.len() / pool.current_num_threads() + 1;
+        pool.install(|| lss_top.par_chunks_mut(chunk_len).enumerate().for_each(|(chunk, range)| {
+            Self::fill_lss_top(range, chunk * chunk_len, &elements, last_level_len, group_size);
+        }));
+        log.log_mem("lss_branch top filled").log_time("lss_branch top filled");
+
+        //generate all levels, levels are independent of each other
+        let config = LevelMapConfig::from_args(args);
+        let mut lss_branch: Vec<M> = pool.install(|| (0..levels).into_par_iter().map(|i| {
+            let (keys, values) = Self::level_pairs(&elements, i, start_level, group_size);
+            M::from_sorted_pairs_with_config(keys, values, &config)
+        }).collect());
+        let lss_leaf = lss_branch.remove(0);
+        log.log_mem("lss levels filled").log_time("lss levels filled");
+
+        let elements = L::from_sorted(elements, group_size);
+        log.log_mem("elements arranged").log_time("elements arranged");
+
+        //return
+        YFT { lss_top, lss_leaf, lss_branch, start_level, last_level_len, elements, group_size, level_search: S::new(levels) }
+    }
+}
+
+impl<M: LevelMap<DataType, DataType>, L: ElementLayout, S: LevelSearch> PredecessorSet<DataType> for YFT<M, L, S> {
+    ///static YFT can not insert
+    fn insert(&mut self, _element: DataType) {
+        panic!("static YFT can not insert");
+    }
+    ///static YFT can not delete
+    fn delete(&mut self, _element: DataType) {
+        panic!("static YFT can not delete");
+    }
+    fn predecessor(&self, number: DataType) -> Option<DataType> {
+        self.predecessor(number)
+    }
+    ///not implemented yet
+    fn successor(&self, _number: DataType) -> Option<DataType> {
+        panic!("sucessor not implemented yet")
+    }
+    fn minimum(&self) -> Option<DataType> {
+        panic!("minimum not implemented yet")
+    }
+    fn maximum(&self) -> Option<DataType> {
+        panic!("maximum not implemented yet")
+    }
+    fn contains(&self, number: DataType) -> bool {
+        self.contains(number)
+    }
+}
\ No newline at end of file
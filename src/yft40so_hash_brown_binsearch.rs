@@ -0,0 +1,9 @@
+extern crate hashbrown;
+
+use self::hashbrown::HashMap;
+use yft40so_generic_binsearch;
+
+pub type DataType = yft40so_generic_binsearch::DataType;
+
+///40 bit Impl with fixed group size, hashbrown hash map, without child pointer and binary search below xft leafs
+pub type YFT = yft40so_generic_binsearch::YFT<HashMap<DataType, DataType>>;
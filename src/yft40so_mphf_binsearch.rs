@@ -0,0 +1,7 @@
+use mphf_map::MphfMap;
+use yft40so_generic_binsearch;
+
+pub type DataType = yft40so_generic_binsearch::DataType;
+
+///40 bit Impl with fixed group size, mphf with fingerprints, without child pointer and binary search below xft leafs
+pub type YFT = yft40so_generic_binsearch::YFT<MphfMap>;
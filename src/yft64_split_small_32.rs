@@ -2,9 +2,12 @@ extern crate fnv;
 
 use args::Args;
 use log::Log;
+use heap_size::{MemoryBreakdown, TrieMemory};
 
 use self::fnv::FnvHashMap;
 use predecessor_set::PredecessorSet;
+use simd_search;
+use level_keys::{calc_path, is_left_child};
 
 pub type DataType = usize;
 pub type SmallType = u32;
@@ -503,6 +506,7 @@ impl YFT {
             count += self.lss_branch[level - 1].len();
         }
         log.print_result(format!("level=-1\tnodes={}", count));
+        self.memory_breakdown().print(log);
     }
 
     fn calc_start_level(elements: &Vec<DataType>, min_start_level: usize, max_lss_level: usize, min_load_factor: usize) -> usize {
@@ -562,7 +566,10 @@ impl YFT {
     }
 
     pub fn contains(&self, query: DataType) -> bool {
-        self.predecessor(query + 1) == Some(query)
+        match self.lss_leaf.get(&calc_path(query, 0, self.start_level)){
+            None => false,
+            Some(&(_, ref values))=> simd_search::contains_u32(values, query as SmallType),
+        }
     }
 
     //query may not belong to existing node
@@ -649,10 +656,7 @@ impl YFT {
     }
 
     fn predecessor_from_array(&self, query: DataType, predecessor: &DataType, elements: &Vec<SmallType>) -> Option<DataType> {
-        let pos = match elements.binary_search(&(query as SmallType)) {
-            Ok(pos) => pos,
-            Err(pos) => pos
-        };
+        let pos = simd_search::lower_bound_u32(elements, query as SmallType);
         return if pos == 0 {
             //test next value greater than search one
             debug_assert!(if let Some(successor) = elements.get(pos) { successor >= &(query as SmallType) } else { true });
@@ -749,17 +753,14 @@ impl YFT {
     }
 } //impl YFT
 
-fn extend_suffix(preffix_source: DataType, suffix: SmallType) -> DataType {
-    (suffix as usize | (usize::from(preffix_source) >> SMALL_TYPE_LEN) << SMALL_TYPE_LEN)
-}
-
-fn calc_path(position: DataType, lss_level: usize, start_level: usize) -> DataType {
-    position >> DataType::from(lss_level + start_level)
+impl TrieMemory for YFT {
+    fn memory_breakdown(&self) -> MemoryBreakdown {
+        MemoryBreakdown::of(&self.lss_top, &self.lss_leaf, &self.lss_branch, &())
+    }
 }
 
-//returns if the node is the left of its parent
-fn is_left_child(path: DataType) -> bool {
-    path % 2 == 0
+fn extend_suffix(preffix_source: DataType, suffix: SmallType) -> DataType {
+    (suffix as usize | (usize::from(preffix_source) >> SMALL_TYPE_LEN) << SMALL_TYPE_LEN)
 }
 
 impl PredecessorSet<DataType> for YFT {
@@ -0,0 +1,316 @@
+extern crate fnv;
+
+/// this module contains a static z-fast trie over the leaf level prefixes of the y-fast tries
+/// the elements are grouped into buckets by their prefix above start_level, like the leaves of lss_leaf
+/// the compacted trie over the bucket prefixes stores every node only once, in the level of its handle
+/// the handle of a node is the 2-fattest number (most trailing zeros) between the length of its name and its extent
+/// the fat binary search always asks the fattest level of the remaining range, so the first levels are the same for all queries
+/// and a hit skips all levels down to the extent of the node instead of one half of the range
+
+use args::Args;
+use log::Log;
+use heap_size::{HeapSize, MemoryBreakdown, TrieMemory};
+use self::fnv::FnvHashMap;
+
+struct ZNode {
+    // length of the common prefix of all bucket prefixes below this node
+    extent: u8,
+    // first bucket of the subtree
+    left: u32,
+    // last bucket of the left child
+    split: u32,
+    // last bucket of the subtree
+    right: u32,
+}
+
+impl HeapSize for ZNode {}
+
+/// (a, b] must not be empty, returns the number in it with the most trailing zeros
+#[inline]
+fn fattest(a: usize, b: usize) -> usize {
+    b & (!0usize << (63 - (a ^ b).leading_zeros()))
+}
+
+/// true, if a and b (both width bits long) share the first len bits
+#[inline]
+fn shares_prefix(a: u64, b: u64, len: usize, width: usize) -> bool {
+    len == 0 || (a ^ b) >> (width - len) == 0
+}
+
+#[inline]
+fn common_prefix_len(a: u64, b: u64, width: usize) -> usize {
+    if a == b {
+        width
+    } else {
+        (a ^ b).leading_zeros() as usize - (64 - width)
+    }
+}
+
+///static z-fast trie for elements smaller than 2^bits (up to 64 bits), binary search inside the buckets
+pub struct ZFT {
+    //== lss leaf level, elements with the same bits above it are in one bucket
+    start_level: usize,
+    // bits of the bucket prefixes
+    width: usize,
+    // node with the handle length h at position width - h - 1 (level width - h, keyed like calc_path), the root is stored separately
+    levels: Vec<FnvHashMap<u64, ZNode>>,
+    root: Option<ZNode>,
+    // sorted prefixes of all buckets
+    prefixes: Vec<u64>,
+    // position of the first element of every bucket + number of elements
+    bucket_starts: Vec<usize>,
+    elements: Vec<u64>,
+}
+
+impl ZFT {
+    ///elements must be sorted ascending!
+    pub fn new(mut elements: Vec<u64>, bits: usize, args: &Args, log: &mut Log) -> ZFT {
+        if elements.len() == 0 {
+            panic!("Input is empty");
+        }
+        assert!(bits > 0 && bits <= 64, "Bit length has to be between 1 and 64");
+        elements.dedup();
+        let start_level = if let Some(start_level) = args.fixed_leaf_level {
+            start_level
+        } else {
+            ZFT::calc_start_level(&elements, args.min_start_level, bits - args.max_lss_level, args.min_start_level_load_factor)
+        };
+        assert!(start_level < bits, "Leaf level has to be smaller than {}", bits);
+        let width = bits - start_level;
+        log.log_time("start level calculated");
+
+        let mut prefixes = Vec::new();
+        let mut bucket_starts = Vec::new();
+        for (pos, value) in elements.iter().enumerate() {
+            //check array is sorted
+            debug_assert!(pos == 0 || *value > elements[pos - 1]);
+            let prefix = *value >> start_level;
+            if prefixes.last() != Some(&prefix) {
+                prefixes.push(prefix);
+                bucket_starts.push(pos);
+            }
+        }
+        bucket_starts.push(elements.len());
+        assert!(prefixes.len() < u32::max_value() as usize, "Too many buckets");
+        log.log_mem("buckets filled").log_time("buckets filled");
+
+        let mut zft = ZFT {
+            start_level,
+            width,
+            levels: (1..width).map(|_| FnvHashMap::default()).collect(),
+            root: None,
+            prefixes,
+            bucket_starts,
+            elements,
+        };
+        if zft.prefixes.len() > 1 {
+            let last = zft.prefixes.len() - 1;
+            zft.insert_nodes(0, last, 0);
+        }
+        log.log_mem("trie filled").log_time("trie filled");
+        zft
+    }
+
+    /// inserts the node of the buckets first to last (first < last) and all nodes below
+    /// name_len is the extent of the parent + 1 (0 for the root)
+    fn insert_nodes(&mut self, first: usize, last: usize, name_len: usize) {
+        let extent = common_prefix_len(self.prefixes[first], self.prefixes[last], self.width);
+        //first bucket, that continues the extent with a 1
+        let bit = self.width - extent - 1;
+        let mut range = (first + 1, last);
+        while range.0 < range.1 {
+            let middle = (range.0 + range.1) / 2;
+            if self.prefixes[middle] >> bit & 1 == 1 {
+                range = (range.0, middle);
+            } else {
+                range = (middle + 1, range.1);
+            }
+        }
+        let split = range.0 - 1;
+        let node = ZNode { extent: extent as u8, left: first as u32, split: split as u32, right: last as u32 };
+        if name_len == 0 {
+            self.root = Some(node);
+        } else {
+            let handle = fattest(name_len - 1, extent);
+            let key = self.prefixes[first] >> (self.width - handle);
+            self.levels[self.width - handle - 1].insert(key, node);
+        }
+        if split > first {
+            self.insert_nodes(first, split, extent + 1);
+        }
+        if last > split + 1 {
+            self.insert_nodes(split + 1, last, extent + 1);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    #[inline]
+    fn node(&self, handle: usize, prefix: u64) -> Option<&ZNode> {
+        if handle >= self.width {
+            //only leaves are that long
+            None
+        } else {
+            self.levels[self.width - handle - 1].get(&(prefix >> (self.width - handle)))
+        }
+    }
+
+    #[inline]
+    fn extent_matches(&self, node: &ZNode, prefix: u64) -> bool {
+        shares_prefix(self.prefixes[node.left as usize], prefix, node.extent as usize, self.width)
+    }
+
+    /// number of buckets with a smaller prefix, search steps and hash misses
+    /// fat binary search for the lowest node, whose handle is a prefix of the query prefix
+    /// if its extent is a prefix too, the exit node is its child on the side of the query
+    fn rank_buckets(&self, prefix: u64) -> (usize, u32, u32) {
+        let mut search_steps = 0;
+        let mut hash_miss = 0;
+        let root = match self.root {
+            Some(ref root) => root,
+            None => return (if self.prefixes[0] < prefix { 1 } else { 0 }, search_steps, hash_miss),
+        };
+        let mut node = root;
+        let mut exit_found = !self.extent_matches(root, prefix);
+        //handles of the lower nodes on the path of the query are in (search_range.0, search_range.1]
+        let mut search_range = (root.extent as usize, self.width);
+        while !exit_found && search_range.0 < search_range.1 {
+            search_steps += 1;
+            let handle = fattest(search_range.0, search_range.1);
+            match self.node(handle, prefix) {
+                Some(found) => {
+                    node = found;
+                    if self.extent_matches(found, prefix) {
+                        search_range = (found.extent as usize, search_range.1);
+                    } else {
+                        //query leaves the trie below the handle of this node
+                        exit_found = true;
+                    }
+                }
+                None => {
+                    hash_miss += 1;
+                    search_range = (search_range.0, handle - 1);
+                }
+            }
+        }
+        let (first, last) = if exit_found {
+            (node.left as usize, node.right as usize)
+        } else if prefix >> (self.width - node.extent as usize - 1) & 1 == 0 {
+            (node.left as usize, node.split as usize)
+        } else {
+            (node.split as usize + 1, node.right as usize)
+        };
+        //all prefixes of the exit node are on the same side of the query
+        let rank = if prefix <= self.prefixes[first] { first } else { last + 1 };
+        (rank, search_steps, hash_miss)
+    }
+
+    /// lss level (bits above start_level) of the lowest node of an uncompacted trie on the path of prefix, like the exit of the y-fast tries
+    /// 42 (top) if prefix shares no bit with the bucket prefixes, with more than 42 prefix bits top is the width (the levels end at width - 1)
+    fn exit_level(&self, prefix: u64, rank: usize) -> u32 {
+        //the longest common prefix is shared with one of the neighbours in sorted order
+        let mut depth = 0;
+        if rank > 0 {
+            depth = common_prefix_len(self.prefixes[rank - 1], prefix, self.width);
+        }
+        if rank < self.prefixes.len() {
+            depth = depth.max(common_prefix_len(self.prefixes[rank], prefix, self.width));
+        }
+        if depth == 0 {
+            self.width.max(42) as u32
+        } else {
+            (self.width - depth) as u32
+        }
+    }
+
+    /// position of the first element, that is not smaller than query
+    #[inline]
+    fn lower_bound(&self, query: u64, bucket: usize) -> usize {
+        let (start, end) = (self.bucket_starts[bucket], self.bucket_starts[bucket + 1]);
+        match self.elements[start..end].binary_search(&query) {
+            Ok(pos) | Err(pos) => start + pos,
+        }
+    }
+
+    /// largest element smaller than query
+    pub fn predecessor_u64(&self, query: u64) -> Option<u64> {
+        if query <= self.elements[0] {
+            return None;
+        }
+        let prefix = query >> self.start_level;
+        let (bucket, _, _) = self.rank_buckets(prefix);
+        Some(self.predecessor_in_bucket(query, prefix, bucket))
+    }
+
+    /// (predecessor, exit level (see exit_level), search steps, hash misses)
+    pub fn predecessor_with_stats_u64(&self, query: u64) -> (Option<u64>, u32, u32, u32) {
+        if query <= self.elements[0] {
+            return (None, 0, 0, 0);
+        }
+        let prefix = query >> self.start_level;
+        let (bucket, search_steps, hash_miss) = self.rank_buckets(prefix);
+        (Some(self.predecessor_in_bucket(query, prefix, bucket)), self.exit_level(prefix, bucket), search_steps, hash_miss)
+    }
+
+    /// bucket == number of buckets with a smaller prefix than the prefix of query, query is larger than the first element
+    #[inline]
+    fn predecessor_in_bucket(&self, query: u64, prefix: u64, bucket: usize) -> u64 {
+        let position = if bucket < self.prefixes.len() && self.prefixes[bucket] == prefix {
+            self.lower_bound(query, bucket)
+        } else {
+            self.bucket_starts[bucket]
+        };
+        self.elements[position - 1]
+    }
+
+    ///prints number of nodes per handle level
+    pub fn print_stats(&self, log: &Log) {
+        log.print_result(format!("start_level={}\tprefix_bits={}\tbuckets={}", self.start_level, self.width, self.prefixes.len()));
+        let mut count = self.root.is_some() as usize;
+        for level in 1..self.width {
+            let len = self.levels[level - 1].len();
+            if len > 0 {
+                log.print_result(format!("level={}\tnodes={}", level, len));
+            }
+            count += len;
+        }
+        log.print_result(format!("level=-1\tnodes={}", count));
+        self.memory_breakdown().print(log);
+    }
+
+    fn calc_start_level(elements: &Vec<u64>, min_start_level: usize, max_lss_level: usize, min_load_factor: usize) -> usize {
+        let mut range = (min_start_level, max_lss_level - 1);
+        while range.0 < range.1 {
+            let candidate = (range.0 + range.1) / 2;
+            if ZFT::calc_nodes_in_level(candidate, elements) / (min_load_factor as f64) >= elements.len() as f64 / 100. {
+                range = (candidate + 1, range.1)
+            } else {
+                range = (range.0, candidate)
+            }
+        }
+        range.1 as usize
+    }
+
+    fn calc_nodes_in_level(level: usize, elements: &Vec<u64>) -> f64 {
+        let mut last_val = elements[0] >> level;
+        let mut count = 1.;
+        for value in elements {
+            let new_val = *value >> level;
+            if new_val != last_val {
+                count += 1.;
+                last_val = new_val;
+            }
+        }
+        count
+    }
+}
+
+impl TrieMemory for ZFT {
+    fn memory_breakdown(&self) -> MemoryBreakdown {
+        let mut breakdown = MemoryBreakdown::of(&(), &self.prefixes, &self.levels, &self.elements);
+        breakdown.lss_leaf += self.bucket_starts.heap_size();
+        breakdown
+    }
+}